@@ -1,2 +1,2 @@
 #!/bin/bash
-cargo test --package llcall_testing --test integration_tests -- deploy_contracts --exact --show-output
\ No newline at end of file
+LLCALL_NODE_URL=${LLCALL_NODE_URL:-127.0.0.1:4000} cargo test --package llcall_testing --test integration_tests -- deploy_contracts --exact --show-output
\ No newline at end of file
@@ -0,0 +1,175 @@
+//! Builds the Sway projects under ./contracts before the tests compile.
+//!
+//! The tests `abigen!` the ABI JSON files and load the .bin files from each project's
+//! out/debug, so those have to exist and be current. Each project is rebuilt with
+//! `forc build` only when its sources are newer than its artifacts, and only with the forc
+//! version pinned in fuel-toolchain.toml.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+const CONTRACTS_DIR: &str = "contracts";
+const TOOLCHAIN_FILE: &str = "fuel-toolchain.toml";
+
+struct ForcProject {
+    dir: PathBuf,
+    name: String,
+}
+
+impl ForcProject {
+    fn artifacts(&self) -> Vec<PathBuf> {
+        let out = self.dir.join("out").join("debug");
+        vec![
+            out.join(format!("{}.bin", self.name)),
+            out.join(format!("{}-abi.json", self.name)),
+        ]
+    }
+
+    fn missing_artifacts(&self) -> Vec<PathBuf> {
+        self.artifacts().into_iter().filter(|a| !a.exists()).collect()
+    }
+
+    /// True when an artifact is missing or older than any source file or Forc.toml.
+    fn is_stale(&self) -> bool {
+        let oldest_artifact = self.artifacts().iter().map(|a| modified(a)).min().flatten();
+        let Some(oldest_artifact) = oldest_artifact else {
+            return true;
+        };
+        let mut sources = vec![self.dir.join("Forc.toml")];
+        collect_files(&self.dir.join("src"), &mut sources);
+        sources.iter().filter_map(|s| modified(s)).any(|m| m > oldest_artifact)
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={TOOLCHAIN_FILE}");
+
+    let projects = find_projects(Path::new(CONTRACTS_DIR));
+    for p in projects.iter() {
+        println!("cargo:rerun-if-changed={}", p.dir.join("Forc.toml").display());
+        println!("cargo:rerun-if-changed={}", p.dir.join("src").display());
+        // a deleted artifact reruns this too, so it is rebuilt or reported missing.
+        for artifact in p.artifacts() {
+            println!("cargo:rerun-if-changed={}", artifact.display());
+        }
+    }
+
+    let stale: Vec<&ForcProject> = projects.iter().filter(|p| p.is_stale()).collect();
+    if stale.is_empty() {
+        return;
+    }
+
+    let expected = pinned_forc_version();
+    match installed_forc_version() {
+        None => {
+            let missing: Vec<PathBuf> = projects.iter().flat_map(|p| p.missing_artifacts()).collect();
+            if missing.is_empty() {
+                println!("cargo:warning=forc not found, using existing (possibly stale) contract artifacts");
+                return;
+            }
+            let channel = pinned_channel();
+            panic!(
+                "\n\nforc was not found on PATH and these contract artifacts are missing:\n{}\n\n\
+                 Install the toolchain pinned in {TOOLCHAIN_FILE} (forc {}) with fuelup:\n\
+                 \tfuelup toolchain install {} && fuelup component add forc@{}\n\n",
+                list(&missing),
+                expected.as_deref().unwrap_or("?"),
+                channel.as_deref().unwrap_or("?"),
+                expected.as_deref().unwrap_or("?"),
+            );
+        }
+        Some(installed) => {
+            if let Some(expected) = expected.as_deref() {
+                if installed != expected {
+                    panic!(
+                        "\n\nforc {installed} is installed but {TOOLCHAIN_FILE} pins forc {expected}.\n\
+                         The abigen'd types in tests/harness.rs must match the pinned compiler.\n\n"
+                    );
+                }
+            }
+        }
+    }
+
+    for p in stale {
+        println!("cargo:warning=building {} with forc", p.dir.display());
+        let status = Command::new("forc")
+            .arg("build")
+            .arg("--path")
+            .arg(&p.dir)
+            .status()
+            .expect("failed to run forc");
+        if !status.success() {
+            panic!("\n\nforc build failed for {}\n\n", p.dir.display());
+        }
+        let missing = p.missing_artifacts();
+        if !missing.is_empty() {
+            panic!("\n\nforc build succeeded but did not produce:\n{}\n\n", list(&missing));
+        }
+    }
+}
+
+/// Every directory under `contracts` with a Forc.toml, with its `[project] name`.
+fn find_projects(root: &Path) -> Vec<ForcProject> {
+    let mut projects = vec![];
+    let Ok(entries) = fs::read_dir(root) else {
+        return projects;
+    };
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        let Ok(manifest) = fs::read_to_string(dir.join("Forc.toml")) else {
+            continue;
+        };
+        if let Some(name) = toml_string(&manifest, "name") {
+            projects.push(ForcProject { dir, name });
+        }
+    }
+    projects.sort_by(|a, b| a.dir.cmp(&b.dir));
+    projects
+}
+
+fn pinned_forc_version() -> Option<String> {
+    toml_string(&fs::read_to_string(TOOLCHAIN_FILE).ok()?, "forc")
+}
+
+fn pinned_channel() -> Option<String> {
+    toml_string(&fs::read_to_string(TOOLCHAIN_FILE).ok()?, "channel")
+}
+
+/// `forc --version` prints e.g. `forc 0.42.1`.
+fn installed_forc_version() -> Option<String> {
+    let output = Command::new("forc").arg("--version").output().ok()?;
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    stdout.split_whitespace().nth(1).map(str::to_string)
+}
+
+/// Value of the first `key = "value"` line. Enough for Forc.toml and fuel-toolchain.toml.
+fn toml_string(contents: &str, key: &str) -> Option<String> {
+    contents.lines().find_map(|line| {
+        let (k, v) = line.split_once('=')?;
+        (k.trim() == key).then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+fn list(paths: &[PathBuf]) -> String {
+    paths.iter().map(|p| format!("\t{}", p.display())).collect::<Vec<_>>().join("\n")
+}
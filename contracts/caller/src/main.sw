@@ -2,12 +2,89 @@ contract;
 
 // Stright from: https://github.com/FuelLabs/fuels-rs/blob/master/packages/fuels/tests/contracts/low_level_caller/src/main.sw
 
-use std::constants::BASE_ASSET_ID;
+use std::constants::{BASE_ASSET_ID, ZERO_B256};
 use std::low_level_call::{call_with_function_selector, CallParams};
 use std::bytes::Bytes;
+use std::alloc::alloc_bytes;
+use std::logging::log;
+
+abi TargetContract {
+    #[storage(write)]
+    fn set0(a: u64);
+    #[storage(write)]
+    fn set1(a: b256);
+    #[storage(write)]
+    fn set2(a: u64, b: u64);
+    #[storage(write)]
+    fn set3(x: DemoStruct);
+    #[storage(write)]
+    fn try_set0_checked(a: u64) -> Result<(), TargetError>;
+}
+
+pub enum TargetError {
+    Zero: (),
+    TooLarge: u64,
+}
+
+pub struct DemoStruct {
+    a: bool,
+    b: [u64; 3],
+    c: u64,
+}
+
+// The call frame call_low_level_call_asm() builds, logged before the call instruction runs.
+pub struct CallFrame {
+    target: b256,
+    param1: u64,
+    param2: u64,
+}
+
+// What forward() calls next. Set with configure_forward().
+storage {
+    next: b256 = ZERO_B256,
+    next_selector: u64 = 0,
+    forward_gas: u64 = 0,
+    max_calls: u64 = 0,
+    entered: u64 = 0,
+    visits: u64 = 0,
+    last_value: u64 = 0,
+}
 
 abi CallerContract {
     fn call_low_level_call(target: ContractId, function_selector: Bytes, calldata: Bytes, single_value_type_arg: bool);
+    fn call_low_level_call_with_gas(target: ContractId, function_selector: Bytes, calldata: Bytes, single_value_type_arg: bool, gas: u64);
+    #[payable]
+    fn call_low_level_call_with_coins(target: ContractId, function_selector: Bytes, calldata: Bytes, single_value_type_arg: bool, coins: u64, asset_id: ContractId);
+
+    fn call_low_level_call_asm(target: ContractId, function_selector: Bytes, calldata: Bytes, single_value_type_arg: bool);
+
+    fn call_typed_set0(target: ContractId, a: u64);
+    fn call_typed_set1(target: ContractId, a: b256);
+    fn call_typed_set2(target: ContractId, a: u64, b: u64);
+    fn call_typed_set3(target: ContractId, x: DemoStruct);
+    fn call_typed_try_set0_checked(target: ContractId, a: u64) -> Result<(), TargetError>;
+
+    #[storage(write)]
+    fn configure_forward(next: ContractId, next_selector: u64, forward_gas: u64, max_calls: u64);
+    #[storage(read, write)]
+    fn forward(value: u64);
+    #[storage(read)]
+    fn forward_state() -> (u64, u64, u64);
+}
+
+// Calls `target` with a call frame holding `selector` and the single word `arg`, the way
+// call_low_level_call_asm() does, forwarding at most `gas`.
+fn call_word(target: b256, selector: u64, arg: u64, gas: u64) {
+    let frame = alloc_bytes(48);
+    frame.write::<b256>(target);
+    frame.add_uint_offset(32).write::<u64>(selector);
+    frame.add_uint_offset(40).write::<u64>(arg);
+
+    let coins = 0;
+    let asset_id = BASE_ASSET_ID;
+    asm(r1: frame, r2: coins, r3: asset_id, r4: gas) {
+        call r1 r2 r3 r4;
+    };
 }
 
 impl CallerContract for Contract {
@@ -27,4 +104,149 @@ impl CallerContract for Contract {
         call_with_function_selector(target, function_selector, calldata, single_value_type_arg, call_params);
     }
     // ANCHOR_END: low_level_call_contract
+
+    // Same as call_low_level_call(), but forwards the gas given by the caller
+    // instead of the fixed 100_000.
+    fn call_low_level_call_with_gas(
+        target: ContractId,
+        function_selector: Bytes,
+        calldata: Bytes,
+        single_value_type_arg: bool,
+        gas: u64,
+    ) {
+        let call_params = CallParams {
+            coins: 0,
+            asset_id: BASE_ASSET_ID,
+            gas: gas,
+        };
+
+        call_with_function_selector(target, function_selector, calldata, single_value_type_arg, call_params);
+    }
+
+    // Same as call_low_level_call(), but forwards `coins` of `asset_id` to the target.
+    // The coins have to be sent to this contract with the call.
+    #[payable]
+    fn call_low_level_call_with_coins(
+        target: ContractId,
+        function_selector: Bytes,
+        calldata: Bytes,
+        single_value_type_arg: bool,
+        coins: u64,
+        asset_id: ContractId,
+    ) {
+        let call_params = CallParams {
+            coins: coins,
+            asset_id: asset_id,
+            gas: 100_000,
+        };
+
+        call_with_function_selector(target, function_selector, calldata, single_value_type_arg, call_params);
+    }
+
+    // Same as call_low_level_call(), but builds the call frame itself instead of going
+    // through std::low_level_call, and runs the call instruction with explicit asm:
+    //
+    //  frame + 0  : target ContractId (32 bytes)
+    //  frame + 32 : param1, the function selector word
+    //  frame + 40 : param2, the argument itself when single_value_type_arg,
+    //               otherwise a pointer to the encoded arguments
+    fn call_low_level_call_asm(
+        target: ContractId,
+        function_selector: Bytes,
+        calldata: Bytes,
+        single_value_type_arg: bool,
+    ) {
+        require(function_selector.len() == 8, "function selector must be one word");
+
+        let frame = alloc_bytes(48);
+        frame.write::<b256>(target.value);
+
+        let param1_ptr = frame.add_uint_offset(32);
+        function_selector.buf.ptr.copy_bytes_to(param1_ptr, 8);
+
+        let param2_ptr = frame.add_uint_offset(40);
+        if single_value_type_arg {
+            require(calldata.len() == 8, "a single value argument must be one word");
+            calldata.buf.ptr.copy_bytes_to(param2_ptr, 8);
+        } else {
+            let args_ptr = asm(p: calldata.buf.ptr) { p: u64 };
+            param2_ptr.write::<u64>(args_ptr);
+        };
+
+        log(CallFrame {
+            target: frame.read::<b256>(),
+            param1: param1_ptr.read::<u64>(),
+            param2: param2_ptr.read::<u64>(),
+        });
+
+        let coins = 0;
+        let asset_id = BASE_ASSET_ID;
+        let gas = 100_000;
+        asm(r1: frame, r2: coins, r3: asset_id, r4: gas) {
+            call r1 r2 r3 r4;
+        };
+    }
+
+    // The same calls made through a typed abi cast instead of call_with_function_selector,
+    // to tell problems in the low level path apart from contract to contract calls in general.
+    fn call_typed_set0(target: ContractId, a: u64) {
+        abi(TargetContract, target.value).set0(a);
+    }
+
+    fn call_typed_set1(target: ContractId, a: b256) {
+        abi(TargetContract, target.value).set1(a);
+    }
+
+    fn call_typed_set2(target: ContractId, a: u64, b: u64) {
+        abi(TargetContract, target.value).set2(a, b);
+    }
+
+    fn call_typed_set3(target: ContractId, x: DemoStruct) {
+        abi(TargetContract, target.value).set3(x);
+    }
+
+    // A target revert always reverts the whole transaction, so the only way to handle a
+    // target error here is for the target to return it. Logs it and hands it back.
+    fn call_typed_try_set0_checked(target: ContractId, a: u64) -> Result<(), TargetError> {
+        let result = abi(TargetContract, target.value).try_set0_checked(a);
+        match result {
+            Result::Err(error) => log(error),
+            Result::Ok(_) => {},
+        }
+        result
+    }
+
+    // Sets up forward(): which contract and selector it calls next, how much gas it forwards
+    // (0 forwards all of it), and how many nested entries of forward() call next before one stops.
+    #[storage(write)]
+    fn configure_forward(next: ContractId, next_selector: u64, forward_gas: u64, max_calls: u64) {
+        storage.next.write(next.value);
+        storage.next_selector.write(next_selector);
+        storage.forward_gas.write(forward_gas);
+        storage.max_calls.write(max_calls);
+    }
+
+    // One hop of a nested low level call chain. Records the visit and passes `value` on to
+    // the configured next contract, which may be another caller, this caller again, or a target.
+    #[storage(read, write)]
+    fn forward(value: u64) {
+        let entered = storage.entered.read() + 1;
+        storage.entered.write(entered);
+        storage.visits.write(storage.visits.read() + 1);
+        storage.last_value.write(value);
+
+        if entered <= storage.max_calls.read() {
+            let gas = storage.forward_gas.read();
+            let gas = if gas == 0 { u64::max() } else { gas };
+            call_word(storage.next.read(), storage.next_selector.read(), value, gas);
+        }
+
+        storage.entered.write(entered - 1);
+    }
+
+    // (visits, last_value, entered)
+    #[storage(read)]
+    fn forward_state() -> (u64, u64, u64) {
+        (storage.visits.read(), storage.last_value.read(), storage.entered.read())
+    }
 }
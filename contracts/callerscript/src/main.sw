@@ -0,0 +1,38 @@
+script;
+
+// Calls the TargetContract setters from a script with a normal typed abi cast.
+// Used as the "script path" when benchmarking against CallerContract.
+
+abi TargetContract {
+    #[storage(write)]
+    fn set0(a: u64);
+    #[storage(write)]
+    fn set1(a: b256);
+    #[storage(write)]
+    fn set2(a: u64, b: u64);
+    #[storage(write)]
+    fn set3(x: DemoStruct);
+}
+
+pub struct DemoStruct {
+    a: bool,
+    b: [u64; 3],
+    c: u64,
+}
+
+pub enum TargetCall {
+    Set0: u64,
+    Set1: b256,
+    Set2: (u64, u64),
+    Set3: DemoStruct,
+}
+
+fn main(target: ContractId, call: TargetCall) {
+    let target_contract = abi(TargetContract, target.value);
+    match call {
+        TargetCall::Set0(a) => target_contract.set0(a),
+        TargetCall::Set1(a) => target_contract.set1(a),
+        TargetCall::Set2(args) => target_contract.set2(args.0, args.1),
+        TargetCall::Set3(x) => target_contract.set3(x),
+    }
+}
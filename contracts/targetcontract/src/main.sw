@@ -5,9 +5,15 @@ use std::constants::{
     ZERO_B256
 };
 use std::storage::storage_api::{read, write};
+use std::storage::storage_vec::*;
 use std::bytes::Bytes;
 use std::convert::TryFrom;
 use std::option::Option::{self, *};
+use std::result::Result::{self, *};
+use std::auth::msg_sender;
+use std::call_frames::{first_param, msg_asset_id};
+use std::logging::log;
+use std::context::msg_amount;
 
 
 
@@ -33,12 +39,67 @@ abi TargetContract {
     #[storage(read)]
     fn get3() -> (u64, u64, u64, bool);
 
+    #[payable]
+    #[storage(write)]
+    fn record_sender();
+    #[storage(read)]
+    fn get_sender() -> (Identity, ContractId, u64);
+
+    #[storage(write)]
+    fn set0_checked(a: u64);
+    #[storage(write)]
+    fn try_set0_checked(a: u64) -> Result<(), TargetError>;
+    fn fail_with_message();
+
+    #[storage(read, write)]
+    fn reenter(value: u64);
+
+    #[storage(write)]
+    fn insert_balance(owner: Identity, amount: u64);
+    #[storage(read)]
+    fn get_balance(owner: Identity) -> u64;
+    #[storage(write)]
+    fn remove_balance(owner: Identity) -> bool;
+
+    #[storage(read, write)]
+    fn push_entry(value: u64);
+    #[storage(read)]
+    fn get_entry(index: u64) -> Option<u64>;
+    #[storage(read)]
+    fn entries_len() -> u64;
+    #[storage(read, write)]
+    fn remove_entry(index: u64) -> u64;
+
+    fn get_configurables() -> (Identity, u64, bool);
+    #[storage(write)]
+    fn set0_with_fee(a: u64);
+    #[storage(write)]
+    fn set0_as_owner(a: u64);
+
+    // set/get pairs generated from tests/utils/codegen.rs, see generate_target_types.
+    //GENERATED-BEGIN abi
+    #[storage(write)]
+    fn set_mixed_struct(x: MixedStruct);
+    #[storage(read)]
+    fn get_mixed_struct() -> MixedStruct;
+    //GENERATED-END abi
 
 }
 
 const STORAGE_KEY1: b256 = 0x0000000000000000000000000000000000000000000000000000000000000001;
 const STORAGE_KEY2: b256 = 0x0000000000000000000000000000000000000000000000000000000000000002;
 
+// Revert code of the fallback, for calls with a selector no method above matches.
+const UNKNOWN_SELECTOR: u64 = 0xbad5e1ec;
+
+// Set at deploy time, see tests/utils/configurables.rs. They are part of the bytecode,
+// so every set of values gives another contract id.
+configurable {
+    OWNER: Identity = Identity::Address(Address { value: ZERO_B256 }),
+    FEE: u64 = 0,
+    FEE_ENABLED: bool = false,
+}
+
 
 storage {
     var0: u64 = 0,
@@ -46,6 +107,39 @@ storage {
     var2: u64 = 0,
     var3: u64 = 0,
     var4: bool = false,
+    sender_is_contract: bool = false,
+    sender: b256 = ZERO_B256,
+    sender_asset: b256 = ZERO_B256,
+    sender_amount: u64 = 0,
+    balances: StorageMap<Identity, u64> = StorageMap {},
+    entries: StorageVec<u64> = StorageVec {},
+    //GENERATED-BEGIN storage
+    mixed_struct: MixedStruct = MixedStruct { flag: false, small: 0, id: ZERO_B256, pair: [0, 0] },
+    //GENERATED-END storage
+}
+
+// CallerContract's forward(), for calling back into the contract that called us.
+abi Forwarder {
+    #[storage(read, write)]
+    fn forward(value: u64);
+}
+
+// Largest value set0_checked() accepts.
+const MAX_CHECKED_VALUE: u64 = 1_000;
+
+pub enum TargetError {
+    Zero: (),
+    TooLarge: u64,
+}
+
+fn check(a: u64) -> Result<(), TargetError> {
+    if a == 0 {
+        return Err(TargetError::Zero);
+    }
+    if a > MAX_CHECKED_VALUE {
+        return Err(TargetError::TooLarge(a));
+    }
+    Ok(())
 }
 
 pub struct DemoStruct {
@@ -54,6 +148,15 @@ pub struct DemoStruct {
     c: u64,
 }
 
+//GENERATED-BEGIN types
+pub struct MixedStruct {
+    flag: bool,
+    small: u8,
+    id: b256,
+    pair: [u32; 2],
+}
+//GENERATED-END types
+
 impl TargetContract for Contract {
 
     #[storage(write)]
@@ -113,4 +216,140 @@ impl TargetContract for Contract {
         (storage.var1.read(), storage.var2.read(), storage.var3.read(), storage.var4.read())
     }
 
+    // Records who called and what was forwarded with the call, so the harness can check
+    // the identity a low level call arrives with.
+    #[payable]
+    #[storage(write)]
+    fn record_sender() {
+        match msg_sender().unwrap() {
+            Identity::Address(address) => {
+                storage.sender_is_contract.write(false);
+                storage.sender.write(address.value);
+            },
+            Identity::ContractId(contract_id) => {
+                storage.sender_is_contract.write(true);
+                storage.sender.write(contract_id.value);
+            },
+        }
+        storage.sender_asset.write(msg_asset_id().value);
+        storage.sender_amount.write(msg_amount());
+    }
+    #[storage(read)]
+    fn get_sender() -> (Identity, ContractId, u64) {
+        let sender = if storage.sender_is_contract.read() {
+            Identity::ContractId(ContractId::from(storage.sender.read()))
+        } else {
+            Identity::Address(Address::from(storage.sender.read()))
+        };
+        (sender, ContractId::from(storage.sender_asset.read()), storage.sender_amount.read())
+    }
+
+    // Like set0(), but reverts with a logged TargetError for 0 or anything above
+    // MAX_CHECKED_VALUE, to see how a target revert looks after a low level call.
+    #[storage(write)]
+    fn set0_checked(a: u64) {
+        require(a != 0, TargetError::Zero);
+        require(a <= MAX_CHECKED_VALUE, TargetError::TooLarge(a));
+        storage.var0.write(a);
+    }
+
+    // Same checks as set0_checked(), but returns the error instead of reverting, so a
+    // calling contract can handle it. A revert can't be caught by the caller.
+    #[storage(write)]
+    fn try_set0_checked(a: u64) -> Result<(), TargetError> {
+        let result = check(a);
+        if result.is_ok() {
+            storage.var0.write(a);
+        }
+        result
+    }
+
+    fn fail_with_message() {
+        require(false, "TargetContract refused the call");
+    }
+
+    // Counts its calls in var0 and calls straight back into the calling contract's
+    // forward(), to test reentrancy. The caller decides when to stop.
+    #[storage(read, write)]
+    fn reenter(value: u64) {
+        storage.var0.write(storage.var0.read() + 1);
+        match msg_sender().unwrap() {
+            Identity::ContractId(caller) => abi(Forwarder, caller.value).forward(value),
+            Identity::Address(_) => {},
+        }
+    }
+
+    // Keyed writes, to see where an entry lands when its key comes through a low level
+    // call. A wrongly encoded key puts the entry in another slot, not in none.
+    #[storage(write)]
+    fn insert_balance(owner: Identity, amount: u64) {
+        storage.balances.insert(owner, amount);
+    }
+    #[storage(read)]
+    fn get_balance(owner: Identity) -> u64 {
+        storage.balances.get(owner).try_read().unwrap_or(0)
+    }
+    #[storage(write)]
+    fn remove_balance(owner: Identity) -> bool {
+        storage.balances.remove(owner)
+    }
+
+    #[storage(read, write)]
+    fn push_entry(value: u64) {
+        storage.entries.push(value);
+    }
+    #[storage(read)]
+    fn get_entry(index: u64) -> Option<u64> {
+        match storage.entries.get(index) {
+            Some(entry) => Some(entry.read()),
+            None => None,
+        }
+    }
+    #[storage(read)]
+    fn entries_len() -> u64 {
+        storage.entries.len()
+    }
+    // Reverts when `index` is out of bounds, moving every later entry down by one otherwise.
+    #[storage(read, write)]
+    fn remove_entry(index: u64) -> u64 {
+        storage.entries.remove(index)
+    }
+
+    fn get_configurables() -> (Identity, u64, bool) {
+        (OWNER, FEE, FEE_ENABLED)
+    }
+
+    // Stores `a` plus FEE, when FEE_ENABLED.
+    #[storage(write)]
+    fn set0_with_fee(a: u64) {
+        let fee = if FEE_ENABLED { FEE } else { 0 };
+        storage.var0.write(a + fee);
+    }
+
+    // Like set0(), but only for OWNER. Through CallerContract the sender is the caller.
+    #[storage(write)]
+    fn set0_as_owner(a: u64) {
+        require(msg_sender().unwrap() == OWNER, "sender is not the configured OWNER");
+        storage.var0.write(a);
+    }
+
+    //GENERATED-BEGIN impl
+    #[storage(write)]
+    fn set_mixed_struct(x: MixedStruct) {
+        storage.mixed_struct.write(x);
+    }
+    #[storage(read)]
+    fn get_mixed_struct() -> MixedStruct {
+        storage.mixed_struct.read()
+    }
+    //GENERATED-END impl
+
+}
+
+// Called for any selector TargetContract doesn't implement, e.g. a low level call with a
+// typo in the signature. Logs the selector it got and reverts with UNKNOWN_SELECTOR.
+#[fallback]
+fn fallback() {
+    log(first_param());
+    revert(UNKNOWN_SELECTOR);
 }
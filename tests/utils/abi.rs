@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+
+use fuel_crypto::Hasher;
+use serde_json::Value;
+
+/// A type from a forc ABI JSON, with generics already substituted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    Unit,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    B256,
+    RawPtr,
+    Str(usize),
+    Array(Box<AbiType>, usize),
+    Tuple(Vec<AbiType>),
+    Struct {
+        name: String,
+        fields: Vec<(String, AbiType)>,
+        generics: Vec<AbiType>,
+    },
+    Enum {
+        name: String,
+        variants: Vec<(String, AbiType)>,
+        generics: Vec<AbiType>,
+    },
+}
+
+impl AbiType {
+    ///
+    /// The type as it appears in a function signature, the same way `fn_selector!` spells
+    /// it: `u64`, `b256`, `a[u64;3]`, `s(bool,a[u64;3],u64)`, `e<u64>((),u64)`, ...
+    ///
+    pub fn signature(&self) -> String {
+        let list = |types: &mut dyn Iterator<Item = &AbiType>| {
+            types.map(AbiType::signature).collect::<Vec<_>>().join(",")
+        };
+        let generics = |generics: &[AbiType]| match generics.is_empty() {
+            true => String::new(),
+            false => format!("<{}>", list(&mut generics.iter())),
+        };
+        match self {
+            AbiType::Unit => "()".to_string(),
+            AbiType::Bool => "bool".to_string(),
+            AbiType::U8 => "u8".to_string(),
+            AbiType::U16 => "u16".to_string(),
+            AbiType::U32 => "u32".to_string(),
+            AbiType::U64 => "u64".to_string(),
+            AbiType::B256 => "b256".to_string(),
+            AbiType::RawPtr => "rawptr".to_string(),
+            AbiType::Str(len) => format!("str[{len}]"),
+            AbiType::Array(element, len) => format!("a[{};{len}]", element.signature()),
+            AbiType::Tuple(elements) => format!("({})", list(&mut elements.iter())),
+            AbiType::Struct { fields, generics: g, .. } => {
+                format!("s{}({})", generics(g), list(&mut fields.iter().map(|(_, t)| t)))
+            }
+            AbiType::Enum { variants, generics: g, .. } => {
+                format!("e{}({})", generics(g), list(&mut variants.iter().map(|(_, t)| t)))
+            }
+        }
+    }
+
+    /// The type as written in Sway, e.g. `DemoStruct`, `[u64; 3]`, `(u64, u64)`.
+    pub fn sway_name(&self) -> String {
+        match self {
+            AbiType::Unit => "()".to_string(),
+            AbiType::RawPtr => "raw_ptr".to_string(),
+            AbiType::Str(len) => format!("str[{len}]"),
+            AbiType::Array(element, len) => format!("[{}; {len}]", element.sway_name()),
+            AbiType::Tuple(elements) => {
+                format!("({})", elements.iter().map(AbiType::sway_name).collect::<Vec<_>>().join(", "))
+            }
+            AbiType::Struct { name, .. } | AbiType::Enum { name, .. } => name.clone(),
+            _ => self.signature(),
+        }
+    }
+
+    /// Fits in a single word, so a low-level call can pass it by value.
+    pub fn is_single_word(&self) -> bool {
+        matches!(self, AbiType::Bool | AbiType::U8 | AbiType::U16 | AbiType::U32 | AbiType::U64 | AbiType::Unit)
+    }
+}
+
+/// One function of a contract ABI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiFunction {
+    pub name: String,
+    pub inputs: Vec<(String, AbiType)>,
+    pub output: AbiType,
+}
+
+impl AbiFunction {
+    /// `name(arg,arg)`, the string `fn_selector!` hashes.
+    pub fn signature(&self) -> String {
+        let args: Vec<String> = self.inputs.iter().map(|(_, t)| t.signature()).collect();
+        format!("{}({})", self.name, args.join(","))
+    }
+
+    /// First 4 bytes of sha256(signature), left-padded to a word like `fn_selector!`.
+    pub fn selector(&self) -> Vec<u8> {
+        let hash = Hasher::hash(self.signature().as_bytes());
+        [[0u8; 4].as_slice(), &hash[..4]].concat()
+    }
+
+    /// The selector as the word a call frame carries in param1.
+    pub fn selector_word(&self) -> u64 {
+        selector_word(&self.selector())
+    }
+}
+
+/// A selector from `fn_selector!` or `AbiFunction::selector` as the single word a call frame,
+/// or CallerContract's forward(), carries in param1.
+pub fn selector_word(selector: &[u8]) -> u64 {
+    u64::from_be_bytes(selector[..8].try_into().expect("a selector is one word"))
+}
+
+/// A parsed ABI JSON: its functions and every named struct / enum it declares.
+#[derive(Debug, Clone, Default)]
+pub struct Abi {
+    pub functions: Vec<AbiFunction>,
+    pub types: Vec<AbiType>,
+}
+
+impl Abi {
+    pub fn function(&self, name: &str) -> Option<&AbiFunction> {
+        self.functions.iter().find(|f| f.name == name)
+    }
+
+    pub fn by_selector(&self, selector_word: u64) -> Option<&AbiFunction> {
+        self.functions.iter().find(|f| f.selector_word() == selector_word)
+    }
+
+    /// A struct or enum declared by the ABI, by its Sway name.
+    pub fn named_type(&self, name: &str) -> Option<&AbiType> {
+        self.types.iter().find(|t| matches!(t, AbiType::Struct { name: n, .. } | AbiType::Enum { name: n, .. } if n == name))
+    }
+}
+
+/// `<project>/out/debug/<name>.bin` -> `<project>/out/debug/<name>-abi.json`.
+pub fn abi_path_for_binary(binary_path: &str) -> String {
+    format!("{}-abi.json", binary_path.trim_end_matches(".bin"))
+}
+
+pub fn load_abi(path: &str) -> io::Result<Abi> {
+    let value: Value = serde_json::from_str(&fs::read_to_string(path)?).map_err(|e| invalid(e.to_string()))?;
+    parse_abi(&value).map_err(|e| invalid(format!("{path}: {e}")))
+}
+
+pub fn parse_abi(value: &Value) -> Result<Abi, String> {
+    let declarations: HashMap<u64, &Value> = value["types"]
+        .as_array()
+        .ok_or("ABI without `types`")?
+        .iter()
+        .filter_map(|t| Some((t["typeId"].as_u64()?, t)))
+        .collect();
+    let resolver = Resolver { declarations };
+
+    let mut functions = vec![];
+    for function in value["functions"].as_array().ok_or("ABI without `functions`")? {
+        let inputs = function["inputs"]
+            .as_array()
+            .ok_or("function without `inputs`")?
+            .iter()
+            .map(|input| Ok((input["name"].as_str().unwrap_or_default().to_string(), resolver.application(input, &HashMap::new())?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        functions.push(AbiFunction {
+            name: function["name"].as_str().ok_or("function without `name`")?.to_string(),
+            inputs,
+            output: resolver.application(&function["output"], &HashMap::new())?,
+        });
+    }
+
+    // non-generic structs and enums, for looking types up by name.
+    let mut types = vec![];
+    for declaration in resolver.declarations.values() {
+        let name = declaration["type"].as_str().unwrap_or_default();
+        let generic = declaration["typeParameters"].as_array().map_or(false, |p| !p.is_empty());
+        if (name.starts_with("struct ") || name.starts_with("enum ")) && !generic {
+            types.push(resolver.declaration(declaration, &[], &HashMap::new())?);
+        }
+    }
+    types.sort_by_key(|t| t.sway_name());
+
+    Ok(Abi { functions, types })
+}
+
+struct Resolver<'a> {
+    declarations: HashMap<u64, &'a Value>,
+}
+
+impl Resolver<'_> {
+    /// A `{ "type": id, "typeArguments": [...] }` entry. `generics` maps the type ids of
+    /// `generic T` declarations in scope to what they stand for.
+    fn application(&self, application: &Value, generics: &HashMap<u64, AbiType>) -> Result<AbiType, String> {
+        let id = application["type"].as_u64().ok_or("type application without `type`")?;
+        if let Some(bound) = generics.get(&id) {
+            return Ok(bound.clone());
+        }
+        let arguments = match application["typeArguments"].as_array() {
+            Some(arguments) => arguments.iter().map(|a| self.application(a, generics)).collect::<Result<Vec<_>, _>>()?,
+            None => vec![],
+        };
+        let declaration = self.declarations.get(&id).ok_or(format!("unknown type id {id}"))?;
+        self.declaration(declaration, &arguments, generics)
+    }
+
+    fn declaration(&self, declaration: &Value, arguments: &[AbiType], outer: &HashMap<u64, AbiType>) -> Result<AbiType, String> {
+        let name = declaration["type"].as_str().ok_or("type without a name")?;
+
+        let mut generics = outer.clone();
+        if let Some(parameters) = declaration["typeParameters"].as_array() {
+            for (parameter, argument) in parameters.iter().zip(arguments) {
+                generics.insert(parameter.as_u64().ok_or("bad type parameter")?, argument.clone());
+            }
+        }
+        let components = || -> Result<Vec<(String, AbiType)>, String> {
+            declaration["components"]
+                .as_array()
+                .map(|c| c.as_slice())
+                .unwrap_or_default()
+                .iter()
+                .map(|c| Ok((c["name"].as_str().unwrap_or_default().to_string(), self.application(c, &generics)?)))
+                .collect()
+        };
+
+        Ok(match name {
+            "()" => AbiType::Unit,
+            "bool" => AbiType::Bool,
+            "u8" => AbiType::U8,
+            "u16" => AbiType::U16,
+            "u32" => AbiType::U32,
+            "u64" => AbiType::U64,
+            "b256" => AbiType::B256,
+            "raw untyped ptr" => AbiType::RawPtr,
+            _ if name.starts_with("str[") => {
+                AbiType::Str(name[4..name.len() - 1].parse().map_err(|_| format!("bad string type {name}"))?)
+            }
+            _ if name.starts_with("[_; ") => {
+                let len = name[4..name.len() - 1].parse().map_err(|_| format!("bad array type {name}"))?;
+                let element = components()?.into_iter().next().ok_or("array without element type")?.1;
+                AbiType::Array(Box::new(element), len)
+            }
+            _ if name.starts_with('(') => AbiType::Tuple(components()?.into_iter().map(|(_, t)| t).collect()),
+            _ if name.starts_with("struct ") => AbiType::Struct {
+                name: name["struct ".len()..].to_string(),
+                fields: components()?,
+                generics: arguments.to_vec(),
+            },
+            _ if name.starts_with("enum ") => AbiType::Enum {
+                name: name["enum ".len()..].to_string(),
+                variants: components()?,
+                generics: arguments.to_vec(),
+            },
+            _ => return Err(format!("unsupported type {name}")),
+        })
+    }
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,468 @@
+use std::fs;
+use std::io;
+
+use fuels::{
+    prelude::*,
+    core::codec::{calldata, fn_selector},
+    programs::{
+        call_response::FuelCallResponse,
+        contract::{ContractCallHandler, MultiContractCallHandler},
+    },
+    tx::{field::ScriptData, Receipt, Script, TransactionFee},
+    types::{
+        errors::Error,
+        transaction::{ScriptTransaction, Transaction},
+        Bits256,
+    },
+};
+
+use crate::{
+    utils::results::{self, CaseResult},
+    utils::selectors::{self, SelectorRegistry},
+    utils::simulate::TargetStorage,
+    utils::trace::{self, ScriptMemory},
+    CallerContract, CallerScript, DemoStruct, TargetCall, TargetContract,
+};
+
+pub const BENCH_RESULTS_CSV: &str = "./bench/results.csv";
+pub const BENCH_RESULTS_MD: &str = "./bench/results.md";
+pub const BENCH_BASELINE_CSV: &str = "./bench/baseline.csv";
+
+/// Relative increase in gas used over the baseline that counts as a regression.
+pub const BENCH_REGRESSION_TOLERANCE: f64 = 0.05;
+
+const CSV_HEADER: &str = "function,path,success,gas_used,total_fee,tx_size,calldata_size";
+
+
+//-------------------------------------------------
+// setter cases:
+
+/// One TargetContract setter with the sample arguments used by the tests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetterCase {
+    Set0,
+    Set1,
+    Set2,
+    Set3,
+}
+
+impl SetterCase {
+    pub const ALL: [SetterCase; 4] = [SetterCase::Set0, SetterCase::Set1, SetterCase::Set2, SetterCase::Set3];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SetterCase::Set0 => "set0",
+            SetterCase::Set1 => "set1",
+            SetterCase::Set2 => "set2",
+            SetterCase::Set3 => "set3",
+        }
+    }
+
+    pub fn selector(&self) -> Vec<u8> {
+        match self {
+            SetterCase::Set0 => fn_selector!(set0(u64)),
+            SetterCase::Set1 => fn_selector!(set1(Bits256)),
+            SetterCase::Set2 => fn_selector!(set2(u64, u64)),
+            SetterCase::Set3 => fn_selector!(set3(DemoStruct)),
+        }
+    }
+
+    pub fn calldata(&self) -> Vec<u8> {
+        match self {
+            SetterCase::Set0 => calldata!(11u64),
+            SetterCase::Set1 => calldata!(sample_bits256()),
+            SetterCase::Set2 => calldata!(10u64, 11u64),
+            SetterCase::Set3 => calldata!(sample_demo_struct()),
+        }
+    }
+
+    /// The argument types as written in Sway.
+    pub fn argument_type(&self) -> &'static str {
+        match self {
+            SetterCase::Set0 => "u64",
+            SetterCase::Set1 => "b256",
+            SetterCase::Set2 => "u64, u64",
+            SetterCase::Set3 => "DemoStruct",
+        }
+    }
+
+    /// `single_value_type_arg` as the argument layout calls for it: only a single
+    /// argument that fits in one word is passed by value.
+    pub fn single_value_type_arg(&self) -> bool {
+        matches!(self, SetterCase::Set0)
+    }
+
+    pub fn target_call(&self) -> TargetCall {
+        match self {
+            SetterCase::Set0 => TargetCall::Set0(11u64),
+            SetterCase::Set1 => TargetCall::Set1(sample_bits256()),
+            SetterCase::Set2 => TargetCall::Set2((10u64, 11u64)),
+            SetterCase::Set3 => TargetCall::Set3(sample_demo_struct()),
+        }
+    }
+
+    /// The same call made by CallerContract through a typed `abi(TargetContract, id)` cast.
+    pub fn typed_call(&self, caller: &CallerContract<WalletUnlocked>, target_id: ContractId) -> ContractCallHandler<WalletUnlocked, ()> {
+        let methods = caller.methods();
+        let handler = match self {
+            SetterCase::Set0 => methods.call_typed_set0(target_id, 11u64),
+            SetterCase::Set1 => methods.call_typed_set1(target_id, sample_bits256()),
+            SetterCase::Set2 => methods.call_typed_set2(target_id, 10u64, 11u64),
+            SetterCase::Set3 => methods.call_typed_set3(target_id, sample_demo_struct()),
+        };
+        handler.set_contract_ids(&[Bech32ContractId::from(target_id)])
+    }
+
+    /// What the setter's getter reads back, formatted for comparison tables.
+    pub fn read_back(&self, storage: &TargetStorage) -> String {
+        match self {
+            SetterCase::Set0 => format!("{}", storage.get0),
+            SetterCase::Set1 => format!("0x{}", hex::encode(storage.get1.0)),
+            SetterCase::Set2 => format!("{:?}", storage.get2),
+            SetterCase::Set3 => format!("{:?}", storage.get3),
+        }
+    }
+
+    /// What `read_back` shows when the sample arguments were stored.
+    pub fn expected(&self) -> String {
+        match self {
+            SetterCase::Set0 => "11".to_string(),
+            SetterCase::Set1 => format!("0x{}", hex::encode(sample_bits256().0)),
+            SetterCase::Set2 => format!("{:?}", (10u64, 11u64)),
+            SetterCase::Set3 => format!("{:?}", (1u64, 3u64, 22u64, true)),
+        }
+    }
+
+    /// True when the getter reads back exactly the sample arguments.
+    pub fn stored_correctly(&self, storage: &TargetStorage) -> bool {
+        match self {
+            SetterCase::Set0 => storage.get0 == 11u64,
+            SetterCase::Set1 => storage.get1 == sample_bits256(),
+            SetterCase::Set2 => storage.get2 == (10u64, 11u64),
+            SetterCase::Set3 => storage.get3 == (1u64, 3u64, 22u64, true),
+        }
+    }
+
+    pub fn direct_call(&self, target: &TargetContract<WalletUnlocked>) -> ContractCallHandler<WalletUnlocked, ()> {
+        match self {
+            SetterCase::Set0 => target.methods().set0(11u64),
+            SetterCase::Set1 => target.methods().set1(sample_bits256()),
+            SetterCase::Set2 => target.methods().set2(10u64, 11u64),
+            SetterCase::Set3 => target.methods().set3(sample_demo_struct()),
+        }
+    }
+
+    /// The setter followed by its getter, as one SDK multicall.
+    pub fn multicall(&self, wallet: &WalletUnlocked, target: &TargetContract<WalletUnlocked>) -> MultiContractCallHandler<WalletUnlocked> {
+        let mut handler = MultiContractCallHandler::new(wallet.clone());
+        handler.add_call(self.direct_call(target));
+        match self {
+            SetterCase::Set0 => handler.add_call(target.methods().get0()),
+            SetterCase::Set1 => handler.add_call(target.methods().get1()),
+            SetterCase::Set2 => handler.add_call(target.methods().get2()),
+            SetterCase::Set3 => handler.add_call(target.methods().get3()),
+        };
+        handler
+    }
+
+    /// Sends `multicall` and returns whether it went through, with its receipts.
+    pub async fn send_multicall(&self, multicall: &MultiContractCallHandler<WalletUnlocked>) -> (bool, Vec<Receipt>) {
+        match self {
+            SetterCase::Set0 => receipts_of(multicall.call::<((), u64)>().await),
+            SetterCase::Set1 => receipts_of(multicall.call::<((), Bits256)>().await),
+            SetterCase::Set2 => receipts_of(multicall.call::<((), (u64, u64))>().await),
+            SetterCase::Set3 => receipts_of(multicall.call::<((), (u64, u64, u64, bool))>().await),
+        }
+    }
+}
+
+pub fn sample_bits256() -> Bits256 {
+    Bits256::from_hex_str("0x0101010101010101010101010101010101010101010101010101010101010105").unwrap()
+}
+
+pub fn sample_demo_struct() -> DemoStruct {
+    DemoStruct {
+        a: true,
+        b: [1, 2, 3],
+        c: 22u64,
+    }
+}
+
+
+//-------------------------------------------------
+// call paths:
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallPath {
+    Direct,
+    Typed,
+    LowLevel,
+    Script,
+    Multicall,
+}
+
+impl CallPath {
+    pub const ALL: [CallPath; 5] = [CallPath::Direct, CallPath::Typed, CallPath::LowLevel, CallPath::Script, CallPath::Multicall];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CallPath::Direct => "direct",
+            CallPath::Typed => "typed",
+            CallPath::LowLevel => "low-level",
+            CallPath::Script => "script",
+            CallPath::Multicall => "multicall",
+        }
+    }
+}
+
+/// One measured call, from the transaction that ran. `calldata_size` is the size of its
+/// script data, which holds the call as the path encodes it, including heap data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchRow {
+    pub function: String,
+    pub path: String,
+    pub success: bool,
+    pub gas_used: u64,
+    pub total_fee: u64,
+    pub tx_size: u64,
+    pub calldata_size: usize,
+}
+
+///
+/// Runs every setter over every call path and collects one row per combination.
+///
+pub async fn run_all(
+    wallet: &WalletUnlocked,
+    caller_id: ContractId,
+    target_id: ContractId,
+    script_binary: &str,
+) -> Vec<BenchRow> {
+    let caller = CallerContract::new(Bech32ContractId::from(caller_id), wallet.clone());
+    let target = TargetContract::new(Bech32ContractId::from(target_id), wallet.clone());
+
+    let mut rows = vec![];
+    for case in SetterCase::ALL {
+        for path in CallPath::ALL {
+            let row = run_case(wallet, &caller, &target, target_id, script_binary, case, path).await;
+            println!("{:<5} {:<10} success={} gas_used={}", row.function, row.path, row.success, row.gas_used);
+            rows.push(row);
+        }
+    }
+    rows
+}
+
+async fn run_case(
+    wallet: &WalletUnlocked,
+    caller: &CallerContract<WalletUnlocked>,
+    target: &TargetContract<WalletUnlocked>,
+    target_id: ContractId,
+    script_binary: &str,
+    case: SetterCase,
+    path: CallPath,
+) -> BenchRow {
+    let target_bech32 = Bech32ContractId::from(target_id);
+
+    // each path's handler builds its transaction and it is sent as is, so the sizes and the
+    // fee are those of the transaction that ran.
+    let tx = match path {
+        CallPath::Direct => case.direct_call(target).build_tx().await,
+        CallPath::Typed => case.typed_call(caller, target_id).build_tx().await,
+        CallPath::LowLevel => {
+            caller
+                .methods()
+                .call_low_level_call(
+                    target_id,
+                    Bytes(case.selector()),
+                    Bytes(case.calldata()),
+                    case.single_value_type_arg(),
+                )
+                .set_contract_ids(&[target_bech32])
+                .build_tx()
+                .await
+        }
+        CallPath::Script => {
+            CallerScript::new(wallet.clone(), script_binary)
+                .main(target_id, case.target_call())
+                .set_contract_ids(&[target_bech32])
+                .build_tx()
+                .await
+        }
+        CallPath::Multicall => case.multicall(wallet, target).build_tx().await,
+    };
+    let (tx, success, receipts) = match tx {
+        Ok(tx) => {
+            let (success, receipts) = send_tx(wallet, &tx).await;
+            (Some(tx), success, receipts)
+        }
+        Err(error) => {
+            println!("❌ call failed before execution: {:#?}", error);
+            (None, false, vec![])
+        }
+    };
+
+    let mut case_result = CaseResult::new(path.name(), tx.as_ref().map(results::tx_id), &receipts);
+    case_result.success &= success;
+    let memory = match &tx {
+        Some(tx) => ScriptMemory::load(wallet, tx).await.ok(),
+        None => None,
+    };
+    let registry = SelectorRegistry::scan(selectors::CONTRACTS_DIR).unwrap_or_default();
+    let case_result = case_result.with_receipts(trace::build(&receipts, memory.as_ref(), &[], &registry), &receipts);
+    results::save(&case_result).unwrap();
+
+    let total_fee = match &tx {
+        Some(tx) => charged_fee(wallet, tx, &receipts).await.unwrap_or(0),
+        None => 0,
+    };
+    BenchRow {
+        function: case.name().to_string(),
+        path: path.name().to_string(),
+        success,
+        gas_used: gas_used(&receipts),
+        total_fee,
+        tx_size: tx.as_ref().map_or(0, |tx| tx.metered_bytes_size() as u64),
+        calldata_size: tx.as_ref().map_or(0, script_data_size),
+    }
+}
+
+/// Sends `tx` and returns whether it went through, with its receipts.
+async fn send_tx(wallet: &WalletUnlocked, tx: &ScriptTransaction) -> (bool, Vec<Receipt>) {
+    let provider = match wallet.try_provider() {
+        Ok(provider) => provider,
+        Err(error) => return receipts_of::<()>(Err(error)),
+    };
+    match provider.send_transaction(tx).await {
+        Ok(receipts) => (true, receipts),
+        Err(error) => receipts_of::<()>(Err(error)),
+    }
+}
+
+///
+/// What the node charged for `tx`: its metered bytes plus the gas its receipts show it
+/// used, at its gas price. Unused gas up to the limit is refunded, so this is less than
+/// what `estimate_transaction_cost` reserves.
+///
+async fn charged_fee(wallet: &WalletUnlocked, tx: &ScriptTransaction, receipts: &[Receipt]) -> Option<u64> {
+    let params = wallet.try_provider().ok()?.chain_info().await.ok()?.consensus_parameters;
+    TransactionFee::checked_from_values(&params, tx.metered_bytes_size() as u64, gas_used(receipts), tx.gas_price())
+        .map(|fee| fee.total())
+}
+
+/// Size of `tx`'s script data: the call, its arguments and their heap data, as the path encodes them.
+pub fn script_data_size(tx: &ScriptTransaction) -> usize {
+    let script: Script = tx.clone().into();
+    script.script_data().len()
+}
+
+/// Receipts of a call whether it succeeded or reverted.
+pub fn receipts_of<D>(result: Result<FuelCallResponse<D>>) -> (bool, Vec<Receipt>) {
+    match result {
+        Ok(response) => (true, response.receipts),
+        Err(Error::RevertTransactionError { receipts, .. }) => (false, receipts),
+        Err(error) => {
+            println!("❌ call failed before execution: {:#?}", error);
+            (false, vec![])
+        }
+    }
+}
+
+/// Gas used by the whole script, taken from its ScriptResult receipt.
+pub fn gas_used(receipts: &[Receipt]) -> u64 {
+    receipts
+        .iter()
+        .find_map(|r| match r {
+            Receipt::ScriptResult { gas_used, .. } => Some(*gas_used),
+            _ => None,
+        })
+        .unwrap_or(0)
+}
+
+
+//-------------------------------------------------
+// output & baseline:
+
+pub fn write_results(rows: &[BenchRow]) -> io::Result<()> {
+    fs::create_dir_all("./bench")?;
+    fs::write(BENCH_RESULTS_CSV, to_csv(rows))?;
+    fs::write(BENCH_RESULTS_MD, to_markdown(rows))?;
+    println!("Benchmark results written to {} and {}", BENCH_RESULTS_CSV, BENCH_RESULTS_MD);
+    Ok(())
+}
+
+pub fn to_csv(rows: &[BenchRow]) -> String {
+    let mut out = format!("{CSV_HEADER}\n");
+    for r in rows {
+        out.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            r.function, r.path, r.success, r.gas_used, r.total_fee, r.tx_size, r.calldata_size
+        ));
+    }
+    out
+}
+
+pub fn to_markdown(rows: &[BenchRow]) -> String {
+    let mut out = String::from("| function | path | success | gas used | total fee | tx size | calldata size |\n");
+    out.push_str("|---|---|---|---:|---:|---:|---:|\n");
+    for r in rows {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            r.function,
+            r.path,
+            if r.success { "✅" } else { "❌" },
+            r.gas_used,
+            r.total_fee,
+            r.tx_size,
+            r.calldata_size
+        ));
+    }
+    out
+}
+
+pub fn read_rows(path: &str) -> io::Result<Vec<BenchRow>> {
+    let contents = fs::read_to_string(path)?;
+    let invalid = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("bad bench row: {line}"));
+
+    let mut rows = vec![];
+    for line in contents.lines().skip(1).filter(|l| !l.trim().is_empty()) {
+        let f: Vec<&str> = line.split(',').collect();
+        if f.len() != 7 {
+            return Err(invalid(line));
+        }
+        rows.push(BenchRow {
+            function: f[0].to_string(),
+            path: f[1].to_string(),
+            success: f[2].parse().map_err(|_| invalid(line))?,
+            gas_used: f[3].parse().map_err(|_| invalid(line))?,
+            total_fee: f[4].parse().map_err(|_| invalid(line))?,
+            tx_size: f[5].parse().map_err(|_| invalid(line))?,
+            calldata_size: f[6].parse().map_err(|_| invalid(line))?,
+        });
+    }
+    Ok(rows)
+}
+
+///
+/// Compares `current` against `baseline` by (function, path).
+/// Flags gas or fee growth beyond `tolerance`, and calls that used to succeed but now fail.
+///
+pub fn regressions(baseline: &[BenchRow], current: &[BenchRow], tolerance: f64) -> Vec<String> {
+    let exceeds = |old: u64, new: u64| new as f64 > old as f64 * (1.0 + tolerance);
+
+    let mut found = vec![];
+    for cur in current {
+        let Some(base) = baseline.iter().find(|b| b.function == cur.function && b.path == cur.path) else {
+            continue;
+        };
+        let label = format!("{} via {}", cur.function, cur.path);
+        if base.success && !cur.success {
+            found.push(format!("{label}: succeeded in baseline, now fails"));
+        }
+        if exceeds(base.gas_used, cur.gas_used) {
+            found.push(format!("{label}: gas used {} -> {}", base.gas_used, cur.gas_used));
+        }
+        if exceeds(base.total_fee, cur.total_fee) {
+            found.push(format!("{label}: total fee {} -> {}", base.total_fee, cur.total_fee));
+        }
+    }
+    found
+}
@@ -0,0 +1,250 @@
+use std::fs;
+use std::io;
+
+pub const TARGET_SOURCE: &str = "./contracts/targetcontract/src/main.sw";
+pub const HARNESS_SOURCE: &str = "./tests/harness.rs";
+pub const README: &str = "./README.md";
+
+/// A field type, spelled as in Rust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    /// `b256` in Sway, `Bits256` in Rust.
+    B256,
+    Array(&'static FieldType, usize),
+}
+
+/// A struct to add to TargetContract, with its own storage field, setter, getter and test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeSpec {
+    pub name: &'static str,
+    pub fields: &'static [(&'static str, FieldType)],
+}
+
+///
+/// The types TargetContract gets a `set_<type>` / `get_<type>` pair for. Add a type here and
+/// run `generate_target_types`, then rebuild the contracts: the Rust struct comes from abigen.
+///
+pub const GENERATED_TYPES: &[TypeSpec] = &[
+    TypeSpec {
+        name: "MixedStruct",
+        fields: &[
+            ("flag", FieldType::Bool),
+            ("small", FieldType::U8),
+            ("id", FieldType::B256),
+            ("pair", FieldType::Array(&FieldType::U32, 2)),
+        ],
+    },
+];
+
+impl FieldType {
+    fn sway(&self) -> String {
+        match self {
+            FieldType::Bool => "bool".to_string(),
+            FieldType::U8 => "u8".to_string(),
+            FieldType::U16 => "u16".to_string(),
+            FieldType::U32 => "u32".to_string(),
+            FieldType::U64 => "u64".to_string(),
+            FieldType::B256 => "b256".to_string(),
+            FieldType::Array(element, len) => format!("[{}; {len}]", element.sway()),
+        }
+    }
+
+    /// The value the storage field starts with.
+    fn sway_zero(&self) -> String {
+        match self {
+            FieldType::Bool => "false".to_string(),
+            FieldType::B256 => "ZERO_B256".to_string(),
+            FieldType::Array(element, len) => format!("[{}]", vec![element.sway_zero(); *len].join(", ")),
+            _ => "0".to_string(),
+        }
+    }
+
+    /// A sample value as a Rust expression, numbers counting up from `*next`.
+    fn rust_sample(&self, next: &mut u64) -> String {
+        let mut number = || {
+            *next += 1;
+            *next
+        };
+        match self {
+            FieldType::Bool => "true".to_string(),
+            FieldType::U8 => format!("{}u8", number()),
+            FieldType::U16 => format!("{}u16", number()),
+            FieldType::U32 => format!("{}u32", number()),
+            FieldType::U64 => format!("{}u64", number()),
+            FieldType::B256 => format!("Bits256([{}u8; 32])", number()),
+            FieldType::Array(element, len) => {
+                format!("[{}]", (0..*len).map(|_| element.rust_sample(next)).collect::<Vec<_>>().join(", "))
+            }
+        }
+    }
+}
+
+impl TypeSpec {
+    /// `MixedStruct` -> `mixed_struct`, the storage field and the suffix of its functions.
+    pub fn snake_name(&self) -> String {
+        let mut snake = String::new();
+        for (i, c) in self.name.chars().enumerate() {
+            if c.is_uppercase() && i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        snake
+    }
+
+    fn rust_sample(&self) -> String {
+        let mut next = 0;
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, ty)| format!("{name}: {}", ty.rust_sample(&mut next)))
+            .collect();
+        format!("{} {{ {} }}", self.name, fields.join(", "))
+    }
+}
+
+/// A marked region of a file, rewritten as a whole on every run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub path: &'static str,
+    pub name: &'static str,
+    pub begin: String,
+    pub end: String,
+    pub content: String,
+}
+
+impl Section {
+    fn code(path: &'static str, name: &'static str, content: String) -> Section {
+        Section {
+            path,
+            name,
+            begin: format!("//GENERATED-BEGIN {name}"),
+            end: format!("//GENERATED-END {name}"),
+            content,
+        }
+    }
+
+    pub fn markdown(path: &'static str, name: &'static str, content: String) -> Section {
+        Section {
+            path,
+            name,
+            begin: format!("<!-- GENERATED-BEGIN {name} -->"),
+            end: format!("<!-- GENERATED-END {name} -->"),
+            content,
+        }
+    }
+
+    /// `text` with the lines between this section's markers replaced by its content.
+    pub fn splice(&self, text: &str) -> Result<String, String> {
+        let missing = |marker: &str| format!("{}: no `{marker}` line", self.path);
+        let begin = text.find(&self.begin).ok_or_else(|| missing(&self.begin))?;
+        let after_begin = begin + text[begin..].find('\n').ok_or_else(|| missing(&self.begin))? + 1;
+        let end = after_begin + text[after_begin..].find(&self.end).ok_or_else(|| missing(&self.end))?;
+        // keep the end marker's indentation.
+        let end = text[..end].rfind('\n').map_or(end, |n| n + 1).max(after_begin);
+        Ok(format!("{}{}{}", &text[..after_begin], self.content, &text[end..]))
+    }
+}
+
+/// Every generated section of every file, for `types`.
+pub fn sections(types: &[TypeSpec]) -> Vec<Section> {
+    let mut structs = String::new();
+    let mut declarations = String::new();
+    let mut storage = String::new();
+    let mut functions = String::new();
+    let mut tests = String::new();
+    let mut readme = String::new();
+
+    for spec in types {
+        let (name, snake) = (spec.name, spec.snake_name());
+
+        structs.push_str(&format!("pub struct {name} {{\n"));
+        for (field, ty) in spec.fields {
+            structs.push_str(&format!("    {field}: {},\n", ty.sway()));
+        }
+        structs.push_str("}\n");
+
+        declarations.push_str(&format!(
+            "    #[storage(write)]\n    fn set_{snake}(x: {name});\n    #[storage(read)]\n    fn get_{snake}() -> {name};\n"
+        ));
+
+        let zero: Vec<String> = spec.fields.iter().map(|(field, ty)| format!("{field}: {}", ty.sway_zero())).collect();
+        storage.push_str(&format!("    {snake}: {name} = {name} {{ {} }},\n", zero.join(", ")));
+
+        functions.push_str(&format!(
+            "    #[storage(write)]\n    fn set_{snake}(x: {name}) {{\n        storage.{snake}.write(x);\n    }}\n    \
+             #[storage(read)]\n    fn get_{snake}() -> {name} {{\n        storage.{snake}.read()\n    }}\n"
+        ));
+
+        tests.push_str(&format!(
+            "\n\
+             ///\n\
+             /// # set_{snake}() then get_{snake}() called directly -> the value round-trips.\n\
+             ///\n\
+             ///#ANCHOR - Generated from codegen::GENERATED_TYPES by generate_target_types.\n\
+             #[tokio::test]\n\
+             async fn test_generated_{snake}_round_trip() {{\n    \
+                 println!(\"Tests that {name} round-trips through TargetContract storage:\");\n\
+             \n    \
+                 let ctx = TestContext::isolated().await;\n    \
+                 let value = {sample};\n    \
+                 let _result = ctx.target.methods().set_{snake}(value.clone()).call().await.unwrap();\n    \
+                 let read = ctx.target.methods().get_{snake}().simulate().await.unwrap().value;\n    \
+                 println!(\"\\n get_{snake}() = {{:#?}}\", read);\n\
+             \n    \
+                 assert_eq!(read, value);\n\
+             \n\
+             }}\n\
+             // cargo test --package llcall_testing --test integration_tests -- test_generated_{snake}_round_trip --exact --show-output\n\
+             \n",
+            sample = spec.rust_sample(),
+        ));
+
+        let fields: Vec<String> = spec.fields.iter().map(|(field, ty)| format!("{field}: {}", ty.sway())).collect();
+        readme.push_str(&format!(
+            "- `{name} {{ {} }}`: `set_{snake}` / `get_{snake}`, tested by `test_generated_{snake}_round_trip`\n",
+            fields.join(", ")
+        ));
+    }
+
+    vec![
+        Section::code(TARGET_SOURCE, "abi", declarations),
+        Section::code(TARGET_SOURCE, "storage", storage),
+        Section::code(TARGET_SOURCE, "types", structs),
+        Section::code(TARGET_SOURCE, "impl", functions),
+        Section::code(HARNESS_SOURCE, "tests", tests),
+        Section::markdown(README, "types", readme),
+    ]
+}
+
+///
+/// Splices every section into its file. With `write` the files are rewritten, otherwise
+/// nothing is touched. Returns the sections whose file content was out of date.
+///
+pub fn generate(types: &[TypeSpec], write: bool) -> io::Result<Vec<String>> {
+    let sections = sections(types);
+    let mut paths: Vec<&str> = sections.iter().map(|s| s.path).collect();
+    paths.dedup();
+
+    let mut stale = vec![];
+    for path in paths {
+        let original = fs::read_to_string(path)?;
+        let mut text = original.clone();
+        for section in sections.iter().filter(|s| s.path == path) {
+            let spliced = section.splice(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if spliced != text {
+                stale.push(format!("{path}: {}", section.name));
+            }
+            text = spliced;
+        }
+        if write && text != original {
+            fs::write(path, text)?;
+        }
+    }
+    Ok(stale)
+}
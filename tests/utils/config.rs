@@ -0,0 +1,218 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use fuels::{accounts::fuel_crypto::SecretKey, prelude::*};
+use serde_json::Value;
+
+use crate::utils::{encoding::{Encoding, ENCODING_ENV}, llcall::DEFAULT_FORWARD_GAS_MARGIN_PERCENT, wallets};
+
+/// The fuel-core dev wallet, funded on `fuel-core run` local nodes.
+pub const WALLET_FROM_FUEL_CORE: &str =
+    "de97d8624a438121b86a1956544bd72ed68cd69f2c99555b08b1e8c51ffd511c";
+
+pub const DEFAULT_CALLER_CONTRACT_BINARY_PATH: &str =
+    "./contracts/caller/out/debug/caller.bin";
+pub const DEFAULT_TARGET_CONTRACT_BINARY_PATH: &str =
+    "./contracts/targetcontract/out/debug/target.bin";
+pub const DEFAULT_CALLER_SCRIPT_BINARY_PATH: &str =
+    "./contracts/callerscript/out/debug/callerscript.bin";
+
+/// Config file read when present. Point LLCALL_CONFIG elsewhere to use another one.
+pub const DEFAULT_CONFIG_FILE: &str = "./llcall.json";
+
+pub const CONFIG_FILE_ENV: &str = "LLCALL_CONFIG";
+pub const NODE_URL_ENV: &str = "LLCALL_NODE_URL";
+pub const SIGNING_KEY_ENV: &str = "LLCALL_SIGNING_KEY";
+pub const KEYSTORE_ENV: &str = "LLCALL_KEYSTORE";
+pub const KEYSTORE_PASSWORD_ENV: &str = "LLCALL_KEYSTORE_PASSWORD";
+pub const MNEMONIC_ENV: &str = "LLCALL_MNEMONIC";
+pub const CALLER_BINARY_ENV: &str = "LLCALL_CALLER_BINARY";
+pub const TARGET_BINARY_ENV: &str = "LLCALL_TARGET_BINARY";
+pub const SCRIPT_BINARY_ENV: &str = "LLCALL_SCRIPT_BINARY";
+pub const FORWARD_GAS_MARGIN_ENV: &str = "LLCALL_FORWARD_GAS_MARGIN";
+
+/// Coins the signing wallet starts with on an embedded node.
+const EMBEDDED_COINS: u64 = 10;
+const EMBEDDED_COIN_AMOUNT: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Node {
+    /// A fuel-core started inside the test process, gone when the test ends.
+    Embedded,
+    /// An already running node, e.g. `127.0.0.1:4000`.
+    Remote(String),
+}
+
+impl Node {
+    fn parse(value: &str) -> Node {
+        match value.trim() {
+            "" | "embedded" => Node::Embedded,
+            url => Node::Remote(url.to_string()),
+        }
+    }
+}
+
+/// Everything the tests and deploy helpers used to take from compile-time constants.
+#[derive(Debug, Clone)]
+pub struct HarnessConfig {
+    pub node: Node,
+    pub signing_key: SecretKey,
+    /// Phrase extra signers are derived from, instead of generating and funding fresh ones.
+    pub mnemonic: Option<String>,
+    pub caller_binary: String,
+    pub target_binary: String,
+    pub script_binary: String,
+    pub forward_gas_margin_percent: u64,
+    /// How `TestContext::encoded_call` encodes arguments.
+    pub encoding: Encoding,
+}
+
+/// Every problem found while resolving the config, reported together.
+#[derive(Debug)]
+pub struct ConfigError(pub Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "invalid harness config:")?;
+        for problem in self.0.iter() {
+            writeln!(f, "\t- {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl HarnessConfig {
+    ///
+    /// Resolves each setting from, in order: environment variables (including a `.env`
+    /// file), the config file, then the defaults. The default node is embedded.
+    /// The signing key and binary paths are validated before anything is returned.
+    ///
+    pub fn load() -> std::result::Result<HarnessConfig, ConfigError> {
+        dotenv::dotenv().ok();
+
+        let mut problems = vec![];
+
+        let file_path = std::env::var(CONFIG_FILE_ENV).unwrap_or_else(|_| DEFAULT_CONFIG_FILE.to_string());
+        let file = match fs::read_to_string(&file_path) {
+            Ok(contents) => match serde_json::from_str::<Value>(&contents) {
+                Ok(value) => value,
+                Err(e) => {
+                    problems.push(format!("{file_path} is not valid JSON: {e}"));
+                    Value::Null
+                }
+            },
+            // a missing default file is fine, a missing explicitly requested one is not.
+            Err(_) if std::env::var(CONFIG_FILE_ENV).is_err() => Value::Null,
+            Err(e) => {
+                problems.push(format!("cannot read {file_path}: {e}"));
+                Value::Null
+            }
+        };
+
+        let setting = |env: &str, key: &str| -> Option<String> {
+            std::env::var(env).ok().or_else(|| match &file[key] {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+        };
+
+        let node = Node::parse(&setting(NODE_URL_ENV, "node_url").unwrap_or_default());
+
+        // a keystore, when given, takes the place of the plaintext key.
+        let signing_key = match setting(KEYSTORE_ENV, "keystore") {
+            Some(keystore) => {
+                let password = std::env::var(KEYSTORE_PASSWORD_ENV).unwrap_or_default();
+                wallets::secret_key_from_keystore(Path::new(&keystore), &password).map_err(|e| {
+                    problems.push(format!("{KEYSTORE_ENV} / keystore: {e} (password from {KEYSTORE_PASSWORD_ENV})"));
+                })
+            }
+            None => {
+                let key = setting(SIGNING_KEY_ENV, "signing_key").unwrap_or_else(|| WALLET_FROM_FUEL_CORE.to_string());
+                SecretKey::from_str(key.trim_start_matches("0x")).map_err(|_| {
+                    problems.push(format!("{SIGNING_KEY_ENV} / signing_key is not a valid secp256k1 secret key"));
+                })
+            }
+        };
+
+        let mnemonic = setting(MNEMONIC_ENV, "mnemonic");
+        if let Some(phrase) = mnemonic.as_deref() {
+            if WalletUnlocked::new_from_mnemonic_phrase(phrase, None).is_err() {
+                problems.push(format!("{MNEMONIC_ENV} / mnemonic is not a valid BIP-39 phrase"));
+            }
+        }
+
+        let binary = |env: &str, key: &str, default: &str, problems: &mut Vec<String>| {
+            let path = setting(env, key).unwrap_or_else(|| default.to_string());
+            if !Path::new(&path).is_file() {
+                problems.push(format!("{env} / {key}: {path} does not exist (did the contracts build?)"));
+            }
+            path
+        };
+        let caller_binary = binary(CALLER_BINARY_ENV, "caller_binary", DEFAULT_CALLER_CONTRACT_BINARY_PATH, &mut problems);
+        let target_binary = binary(TARGET_BINARY_ENV, "target_binary", DEFAULT_TARGET_CONTRACT_BINARY_PATH, &mut problems);
+        let script_binary = binary(SCRIPT_BINARY_ENV, "script_binary", DEFAULT_CALLER_SCRIPT_BINARY_PATH, &mut problems);
+
+        let forward_gas_margin_percent = match setting(FORWARD_GAS_MARGIN_ENV, "forward_gas_margin_percent") {
+            None => DEFAULT_FORWARD_GAS_MARGIN_PERCENT,
+            Some(margin) => margin.parse().unwrap_or_else(|_| {
+                problems.push(format!("{FORWARD_GAS_MARGIN_ENV} / forward_gas_margin_percent: {margin} is not a whole number"));
+                DEFAULT_FORWARD_GAS_MARGIN_PERCENT
+            }),
+        };
+
+        let encoding = match setting(ENCODING_ENV, "encoding") {
+            None => Encoding::default(),
+            Some(encoding) => encoding.parse().unwrap_or_else(|e| {
+                problems.push(format!("{ENCODING_ENV} / encoding: {e}"));
+                Encoding::default()
+            }),
+        };
+
+        match signing_key {
+            Ok(signing_key) if problems.is_empty() => Ok(HarnessConfig {
+                node,
+                signing_key,
+                mnemonic,
+                caller_binary,
+                target_binary,
+                script_binary,
+                forward_gas_margin_percent,
+                encoding,
+            }),
+            _ => Err(ConfigError(problems)),
+        }
+    }
+
+    pub fn is_embedded(&self) -> bool {
+        self.node == Node::Embedded
+    }
+
+    ///
+    /// Connects to the configured node, starting an embedded one if needed, and returns
+    /// the signing wallet. On an embedded node that wallet is funded at genesis.
+    ///
+    pub async fn connect(&self) -> Result<WalletUnlocked> {
+        let mut wallet = WalletUnlocked::new_from_private_key(self.signing_key, None);
+
+        let provider = match &self.node {
+            Node::Remote(url) => Provider::connect(url).await?,
+            Node::Embedded => {
+                let coins = setup_single_asset_coins(
+                    wallet.address(),
+                    BASE_ASSET_ID,
+                    EMBEDDED_COINS,
+                    EMBEDDED_COIN_AMOUNT,
+                );
+                let (provider, _socket) = setup_test_provider(coins, vec![], None, None).await;
+                provider
+            }
+        };
+        wallet.set_provider(provider);
+        Ok(wallet)
+    }
+}
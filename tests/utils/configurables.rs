@@ -0,0 +1,96 @@
+use std::io;
+
+use fuels::{
+    prelude::*,
+    types::Identity,
+};
+use serde_json::{json, Map, Value};
+
+use crate::TargetContractConfigurables;
+
+///
+/// Overrides for TargetContract's `configurable` block; `None` keeps the value compiled
+/// into the binary. They are written into the bytecode, so they change the contract id.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TargetConfigurables {
+    pub owner: Option<Identity>,
+    pub fee: Option<u64>,
+    pub fee_enabled: Option<bool>,
+}
+
+impl TargetConfigurables {
+    pub fn is_empty(&self) -> bool {
+        *self == TargetConfigurables::default()
+    }
+
+    /// Only the overridden values, by their Sway name.
+    pub fn to_json(&self) -> Value {
+        let mut values = Map::new();
+        if let Some(owner) = &self.owner {
+            values.insert("OWNER".to_string(), identity_to_json(owner));
+        }
+        if let Some(fee) = self.fee {
+            values.insert("FEE".to_string(), json!(fee));
+        }
+        if let Some(fee_enabled) = self.fee_enabled {
+            values.insert("FEE_ENABLED".to_string(), json!(fee_enabled));
+        }
+        Value::Object(values)
+    }
+
+    /// The inverse of `to_json`. A missing value, as in manifests written before
+    /// configurables existed, is no override.
+    pub fn from_json(value: &Value) -> io::Result<TargetConfigurables> {
+        let invalid = |name: &str| io::Error::new(io::ErrorKind::InvalidData, format!("invalid configurable {name}"));
+        let owner = match value.get("OWNER") {
+            Some(owner) => Some(identity_from_json(owner).ok_or_else(|| invalid("OWNER"))?),
+            None => None,
+        };
+        let fee = match value.get("FEE") {
+            Some(fee) => Some(fee.as_u64().ok_or_else(|| invalid("FEE"))?),
+            None => None,
+        };
+        let fee_enabled = match value.get("FEE_ENABLED") {
+            Some(fee_enabled) => Some(fee_enabled.as_bool().ok_or_else(|| invalid("FEE_ENABLED"))?),
+            None => None,
+        };
+        Ok(TargetConfigurables { owner, fee, fee_enabled })
+    }
+
+    ///
+    /// What to deploy TargetContract's binary with: `salt` and these overrides. Empty
+    /// overrides leave the binary untouched, so other contracts can be loaded with it too.
+    ///
+    pub fn load_configuration(&self, salt: [u8; 32]) -> LoadConfiguration {
+        let configuration = LoadConfiguration::default().set_salt(salt);
+        if self.is_empty() {
+            return configuration;
+        }
+        let mut configurables = TargetContractConfigurables::new();
+        if let Some(owner) = self.owner {
+            configurables = configurables.set_OWNER(owner);
+        }
+        if let Some(fee) = self.fee {
+            configurables = configurables.set_FEE(fee);
+        }
+        if let Some(fee_enabled) = self.fee_enabled {
+            configurables = configurables.set_FEE_ENABLED(fee_enabled);
+        }
+        configuration.set_configurables(configurables)
+    }
+}
+
+fn identity_to_json(identity: &Identity) -> Value {
+    match identity {
+        Identity::Address(address) => json!({ "Address": format!("0x{address}") }),
+        Identity::ContractId(contract_id) => json!({ "ContractId": format!("0x{contract_id}") }),
+    }
+}
+
+fn identity_from_json(value: &Value) -> Option<Identity> {
+    if let Some(address) = value["Address"].as_str() {
+        return address.parse().ok().map(Identity::Address);
+    }
+    value["ContractId"].as_str()?.parse().ok().map(Identity::ContractId)
+}
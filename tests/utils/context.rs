@@ -0,0 +1,415 @@
+use std::fmt;
+
+use fuels::{
+    prelude::*,
+    programs::contract::ContractCallHandler,
+    tx::Receipt,
+    types::transaction::ScriptTransaction,
+};
+use rand::prelude::Rng;
+
+use crate::{
+    read_cid_from_file,
+    utils::abi,
+    utils::config::HarnessConfig,
+    utils::configurables::TargetConfigurables,
+    utils::encoding::{self, AbiValue},
+    utils::llcall::{self, LowLevelCall, LowLevelPath},
+    utils::manifest::{self, DeployedContract},
+    utils::record,
+    utils::report::{self, ReportPath},
+    utils::results::{self, CaseResult},
+    utils::selectors::{self, SelectorRegistry},
+    utils::simulate::{self, TargetStorage},
+    utils::trace::{self, CallNode, ScriptMemory, TraceContract},
+    utils::wallets,
+    CallerContract, TargetContract,
+};
+
+///
+/// What every TargetContract test needs: the resolved config, a connected and funded
+/// wallet, and CallerContract / TargetContract instances bound to it.
+///
+/// Nothing has to be torn down by hand. An embedded node stops with the test's runtime
+/// when the context goes out of scope, and contracts on a running node are left in place.
+///
+pub struct TestContext {
+    pub config: HarnessConfig,
+    pub provider: Provider,
+    pub wallet: WalletUnlocked,
+    pub caller_id: ContractId,
+    pub target_id: ContractId,
+    pub caller: CallerContract<WalletUnlocked>,
+    pub target: TargetContract<WalletUnlocked>,
+    /// What this context deployed (or reuses), with salts, for recordings to redeploy.
+    pub deployments: Vec<DeployedContract>,
+}
+
+impl TestContext {
+    ///
+    /// A context with its own, freshly deployed CallerContract and TargetContract.
+    ///
+    /// Under LLCALL_SIMULATE on a running node nothing is deployed, the contracts in
+    /// caller_cid.txt and target_cid.txt are shared instead: dry runs leave them as they are.
+    ///
+    pub async fn new() -> TestContext {
+        let config = load_config();
+        let wallet = connect(&config).await;
+        if simulate::simulation_enabled() && !config.is_embedded() {
+            let caller = deployed("CallerContract", "caller_cid.txt", &config.caller_binary);
+            let target = deployed("TargetContract", "target_cid.txt", &config.target_binary);
+            return Self::with_contracts(config, wallet, vec![caller, target]);
+        }
+        let caller = deploy(&wallet, "CallerContract", &config.caller_binary).await;
+        let target = deploy_configured(&wallet, &config.target_binary, TargetConfigurables::default()).await;
+        Self::with_contracts(config, wallet, vec![caller, target])
+    }
+
+    ///
+    /// A context with its own TargetContract, deployed under a random salt, so no other
+    /// test can see or overwrite its storage and tests can run in parallel.
+    ///
+    /// CallerContract keeps no state of its own, so on a running node the one in
+    /// caller_cid.txt is reused. On an embedded node it is deployed too. Under LLCALL_SIMULATE
+    /// it is the same as `new`.
+    ///
+    pub async fn isolated() -> TestContext {
+        let config = load_config();
+        if config.is_embedded() || simulate::simulation_enabled() {
+            return Self::new().await;
+        }
+        let wallet = connect(&config).await;
+        let caller = deployed("CallerContract", "caller_cid.txt", &config.caller_binary);
+        let target = deploy_configured(&wallet, &config.target_binary, TargetConfigurables::default()).await;
+        Self::with_contracts(config, wallet, vec![caller, target])
+    }
+
+    ///
+    /// This context with its TargetContract replaced by a fresh one deployed with
+    /// `configurables`. CallerContract stays the same.
+    ///
+    pub async fn with_target_configurables(self, configurables: TargetConfigurables) -> TestContext {
+        let target = deploy_configured(&self.wallet, &self.config.target_binary, configurables).await;
+        let mut deployments = self.deployments;
+        deployments.retain(|d| d.label != "TargetContract");
+        deployments.push(target);
+        Self::with_contracts(self.config, self.wallet, deployments)
+    }
+
+    fn with_contracts(config: HarnessConfig, wallet: WalletUnlocked, deployments: Vec<DeployedContract>) -> TestContext {
+        let id_of = |label: &str| deployments.iter().find(|d| d.label == label).unwrap().contract_id;
+        let caller_id = id_of("CallerContract");
+        let target_id = id_of("TargetContract");
+        TestContext {
+            provider: wallet.try_provider().unwrap().clone(),
+            caller: CallerContract::new(Bech32ContractId::from(caller_id), wallet.clone()),
+            target: TargetContract::new(Bech32ContractId::from(target_id), wallet.clone()),
+            config,
+            wallet,
+            caller_id,
+            target_id,
+            deployments,
+        }
+    }
+
+    /// Prints the contract ids and wallet address, as every test used to do by hand.
+    pub fn print(&self) {
+        println!("CallerContract contract id = {}", Address::from(*self.caller_id));
+        println!("TargetContract contract id = {}", Address::from(*self.target_id));
+        println!("-----------");
+        println!("wallet0 account:");
+        println!("\t: 0x{}", self.wallet.address());
+        println!("\t: 0x{}\n", Address::from(self.wallet.address()));
+    }
+
+    /// `n` more funded signers, see `wallets::signers`.
+    pub async fn signers(&self, n: usize) -> Vec<WalletUnlocked> {
+        wallets::signers(&self.config, &self.wallet, n).await.unwrap()
+    }
+
+    /// A call to this context's TargetContract through CallerContract.
+    pub fn low_level_call(&self, function_selector: Vec<u8>, calldata: Vec<u8>, single_value_type_arg: bool) -> LowLevelCall {
+        LowLevelCall {
+            target: self.target_id,
+            function_selector,
+            calldata,
+            single_value_type_arg,
+            path: LowLevelPath::Std,
+        }
+    }
+
+    ///
+    /// A call to TargetContract's `function` with its selector from the ABI and `arguments`
+    /// encoded with the configured encoding, instead of `fn_selector!` and `calldata!`.
+    ///
+    pub fn encoded_call(&self, function: &str, arguments: &[AbiValue]) -> LowLevelCall {
+        let target_abi = abi::load_abi(&abi::abi_path_for_binary(&self.config.target_binary)).unwrap();
+        let function = target_abi
+            .function(function)
+            .unwrap_or_else(|| panic!("❌ TargetContract has no {function}()"));
+        let calldata = encoding::encode_arguments(self.config.encoding, function, arguments).unwrap();
+        let single_value_type_arg = function.inputs.len() == 1 && function.inputs[0].1.is_single_word();
+        self.low_level_call(function.selector(), calldata, single_value_type_arg)
+    }
+
+    ///
+    /// Sends `call` through `call_low_level_call`, or its asm twin, and returns what the
+    /// TargetContract getters see afterwards. See `send_then_read`.
+    ///
+    pub async fn send_low_level_call(&self, call: &LowLevelCall, intent: &str) -> Sent {
+        let path = ReportPath::LowLevel(call.path).name();
+        self.send_then_read(llcall::low_level_call_handler(&self.caller, call), path, intent).await
+    }
+
+    /// The call tree of `receipts`, naming this context's contracts.
+    pub fn trace(&self, receipts: &[Receipt]) -> CallNode {
+        trace_of(receipts, None, &self.deployments)
+    }
+
+    /// `trace`, also decoding the arguments `tx` passes behind pointers.
+    pub async fn trace_tx(&self, tx: &ScriptTransaction, receipts: &[Receipt]) -> CallNode {
+        let memory = ScriptMemory::load(&self.wallet, tx).await.unwrap();
+        trace_of(receipts, Some(&memory), &self.deployments)
+    }
+
+    pub async fn send_then_read(&self, handler: ContractCallHandler<WalletUnlocked, ()>, path: &str, intent: &str) -> Sent {
+        send_then_read(&self.wallet, &self.target, &self.deployments, handler, path, intent).await
+    }
+}
+
+
+//-------------------------------------------------
+// helpers:
+
+pub fn load_config() -> HarnessConfig {
+    match HarnessConfig::load() {
+        Ok(config) => config,
+        Err(error) => panic!("❌ {error}"),
+    }
+}
+
+pub async fn connect(config: &HarnessConfig) -> WalletUnlocked {
+    match config.connect().await {
+        Ok(wallet) => wallet,
+        Err(error) => panic!("❌ Problem creating provider: {:#?}", error),
+    }
+}
+
+pub fn random_salt() -> [u8; 32] {
+    let mut rng = rand::thread_rng();
+    rng.gen::<[u8; 32]>()
+}
+
+///
+/// The contract whose id is in `cid_file`, with its salt and configurables from
+/// deployments.json when it is listed there.
+///
+fn deployed(label: &str, cid_file: &str, binary_path: &str) -> DeployedContract {
+    let contract_id = read_cid_from_file(cid_file.to_string()).unwrap();
+    manifest::read_manifest(manifest::DEPLOYMENT_MANIFEST)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|d| d.contract_id == contract_id)
+        .unwrap_or_else(|| DeployedContract {
+            // deployed before deployments.json existed, so it cannot be redeployed on replay.
+            label: label.to_string(),
+            binary_path: binary_path.to_string(),
+            contract_id,
+            salt: [0u8; 32],
+            configurables: (label == "TargetContract").then(TargetConfigurables::default),
+        })
+}
+
+/// The call tree of `receipts`, naming `deployments`.
+fn trace_of(receipts: &[Receipt], memory: Option<&ScriptMemory>, deployments: &[DeployedContract]) -> CallNode {
+    let contracts: Vec<TraceContract> = deployments.iter().map(TraceContract::from_deployment).collect();
+    let registry = SelectorRegistry::scan(selectors::CONTRACTS_DIR).unwrap_or_default();
+    trace::build(receipts, memory, &contracts, &registry)
+}
+
+/// Deploys `binary_path` under a fresh random salt.
+pub async fn deploy(wallet: &WalletUnlocked, label: &str, binary_path: &str) -> DeployedContract {
+    let salt = random_salt();
+    DeployedContract {
+        label: label.to_string(),
+        binary_path: binary_path.to_string(),
+        contract_id: deploy_with_salt(wallet, binary_path, salt).await.into(),
+        salt,
+        configurables: None,
+    }
+}
+
+/// Deploys TargetContract's `binary_path` with `configurables` under a fresh random salt.
+pub async fn deploy_configured(
+    wallet: &WalletUnlocked,
+    binary_path: &str,
+    configurables: TargetConfigurables,
+) -> DeployedContract {
+    let salt = random_salt();
+    DeployedContract {
+        label: "TargetContract".to_string(),
+        binary_path: binary_path.to_string(),
+        contract_id: deploy_with_configurables(wallet, binary_path, salt, &configurables).await.into(),
+        salt,
+        configurables: Some(configurables),
+    }
+}
+
+pub async fn deploy_with_salt(wallet: &WalletUnlocked, binary_path: &str, salt: [u8; 32]) -> Bech32ContractId {
+    // deploy with salt:
+    //println!("salt = {}", hex::encode(salt));
+    deploy_with_configuration(wallet, binary_path, LoadConfiguration::default().set_salt(salt)).await
+}
+
+/// Deploys TargetContract's `binary_path` under `salt` with `configurables` written into it.
+pub async fn deploy_with_configurables(
+    wallet: &WalletUnlocked,
+    binary_path: &str,
+    salt: [u8; 32],
+    configurables: &TargetConfigurables,
+) -> Bech32ContractId {
+    deploy_with_configuration(wallet, binary_path, configurables.load_configuration(salt)).await
+}
+
+async fn deploy_with_configuration(
+    wallet: &WalletUnlocked,
+    binary_path: &str,
+    configuration: LoadConfiguration,
+) -> Bech32ContractId {
+    let c_id = Contract::load_from(
+        binary_path,
+        configuration,)
+        .unwrap()
+        .deploy(wallet, TxParameters::default())
+        .await;
+
+    match c_id {
+        Ok(contractid) => contractid,
+        Err(error) => panic!("❌ Problem deploying the contract: {:#?}", error),
+    }
+}
+
+///
+/// One call sent by `send_then_read`, with what the TargetContract getters saw afterwards.
+/// Its result is only exported once the test has compared its values, see `Sent::export`.
+///
+#[must_use = "export the case with `Sent::export`"]
+#[derive(Debug, Clone)]
+pub struct Sent {
+    pub storage: TargetStorage,
+    pub receipts: Vec<Receipt>,
+    path: String,
+    tx_id: Option<String>,
+    /// The call tree, only built when LLCALL_RESULTS_DIR is set.
+    trace: String,
+    simulated: bool,
+}
+
+impl Sent {
+    fn result(&self) -> CaseResult {
+        CaseResult::new(&self.path, self.tx_id.clone(), &self.receipts)
+    }
+
+    ///
+    /// Saves the case as a `CaseResult` with `expected` and `observed`, when LLCALL_RESULTS_DIR
+    /// is set. It only passes if they are equal. A dry run leaves no result.
+    ///
+    pub fn export<T: fmt::Debug + PartialEq + ?Sized>(&self, expected: &T, observed: &T) {
+        if self.simulated {
+            return;
+        }
+        let result = self
+            .result()
+            .with_values(format!("{expected:?}"), format!("{observed:?}"), expected == observed)
+            .with_receipts(&self.trace, &self.receipts);
+        results::save(&result).unwrap();
+    }
+}
+
+///
+/// Sends `handler`, or dry-runs it when LLCALL_SIMULATE is set, and returns what the
+/// TargetContract getters see afterwards (or would see, when simulating).
+/// `path` names how the call reaches TargetContract in the exported result, e.g. `direct`
+/// or `low-level std`. `intent` describes the decoded arguments and is kept with the tx
+/// when LLCALL_RECORD_DIR is set, together with `deployments` so a replay can put the same
+/// contracts back. A reverted call is exported as a failed `CaseResult` before it panics.
+///
+pub async fn send_then_read(
+    wallet: &WalletUnlocked,
+    target: &TargetContract<WalletUnlocked>,
+    deployments: &[DeployedContract],
+    handler: ContractCallHandler<WalletUnlocked, ()>,
+    path: &str,
+    intent: &str,
+) -> Sent {
+    if simulate::simulation_enabled() {
+        // a call that would revert comes back too, printed with its receipts and logs.
+        let sim = match simulate::simulate_call(wallet, target, handler).await {
+            Ok(sim) => sim,
+            Err(error) => panic!("❌ Problem simulating {intent}: {:#?}", error),
+        };
+        sim.print();
+        return Sent {
+            storage: sim.storage_after,
+            receipts: sim.receipts,
+            path: path.to_string(),
+            tx_id: None,
+            trace: String::new(),
+            simulated: true,
+        };
+    }
+
+    let record_dir = record::record_dir();
+    let results_dir = results::results_dir();
+    if record_dir.is_none() && results_dir.is_none() {
+        let response = handler.call().await.unwrap();
+        return Sent {
+            storage: simulate::read_storage(wallet, target).await.unwrap(),
+            receipts: response.receipts,
+            path: path.to_string(),
+            tx_id: None,
+            trace: String::new(),
+            simulated: false,
+        };
+    }
+
+    // build the tx ourselves so the exact bytes sent are the ones recorded, and its id known.
+    let (tx, receipts) = match results::send(wallet, &handler).await {
+        Ok(sent) => sent,
+        Err(error) => panic!("❌ Problem sending the transaction: {:#?}", error),
+    };
+    let storage = simulate::read_storage(wallet, target).await.unwrap();
+
+    if let Some(dir) = record_dir {
+        record::save(&dir, &record::Recording {
+            test: record::current_test_name(),
+            intent: intent.to_string(),
+            deployments: deployments.to_vec(),
+            tx_bytes: record::tx_bytes(&tx),
+            receipts: record::receipt_lines(&receipts),
+            storage: storage.clone(),
+        }).unwrap();
+    }
+
+    let trace = match results_dir {
+        Some(_) => {
+            let memory = ScriptMemory::load(wallet, &tx).await.ok();
+            trace_of(&receipts, memory.as_ref(), deployments).to_string()
+        }
+        None => String::new(),
+    };
+    let sent = Sent {
+        storage,
+        receipts,
+        path: path.to_string(),
+        tx_id: Some(results::tx_id(&tx)),
+        trace,
+        simulated: false,
+    };
+
+    if let Some(failure) = report::failure(&sent.receipts) {
+        results::save(&sent.result().with_receipts(&sent.trace, &sent.receipts)).unwrap();
+        panic!("❌ {intent} failed: {failure}");
+    }
+    sent
+}
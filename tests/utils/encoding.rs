@@ -0,0 +1,343 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::{json, Map, Value};
+
+use crate::utils::abi::{AbiFunction, AbiType};
+
+/// Encoding a `LowLevelCall`'s calldata is built with, e.g. `legacy` or `flat`.
+pub const ENCODING_ENV: &str = "LLCALL_ENCODING";
+/// Golden vectors for every TargetContract function under both encodings.
+pub const GOLDEN_VECTORS: &str = "./tests/golden/target_encoding.json";
+
+const WORD_SIZE: usize = 8;
+
+/// How arguments and return values are laid out as bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    ///
+    /// The memory layout fuels 0.44 / forc 0.42 use: every value takes whole words,
+    /// numbers and bools right-aligned, strings padded after, enum variants right-aligned
+    /// in the width of the widest one.
+    ///
+    Legacy,
+    ///
+    /// The flat ABI encoding of later toolchains: every value takes its own size and no
+    /// more, `()` takes nothing and enum variants follow the discriminant directly.
+    ///
+    Flat,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 2] = [Encoding::Legacy, Encoding::Flat];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Legacy => "legacy",
+            Encoding::Flat => "flat",
+        }
+    }
+}
+
+/// Legacy, unless built with the `flat-encoding` feature.
+impl Default for Encoding {
+    fn default() -> Self {
+        if cfg!(feature = "flat-encoding") {
+            Encoding::Flat
+        } else {
+            Encoding::Legacy
+        }
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "legacy" => Ok(Encoding::Legacy),
+            "flat" => Ok(Encoding::Flat),
+            other => Err(format!("unknown encoding {other}, expected legacy or flat")),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A value of an `AbiType`, independent of how it is encoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiValue {
+    Unit,
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    B256([u8; 32]),
+    RawPtr(u64),
+    Str(String),
+    Array(Vec<AbiValue>),
+    Tuple(Vec<AbiValue>),
+    Struct(Vec<AbiValue>),
+    /// The variant's index and its value.
+    Enum(u64, Box<AbiValue>),
+}
+
+///
+/// A deterministic value of `ty`: numbers count up from `*next`, bools are true and
+/// enums take their last variant, so every field ends up with a distinct, non-zero value.
+///
+pub fn sample(ty: &AbiType, next: &mut u64) -> AbiValue {
+    let mut number = || {
+        *next += 1;
+        *next
+    };
+    match ty {
+        AbiType::Unit => AbiValue::Unit,
+        AbiType::Bool => AbiValue::Bool(true),
+        AbiType::U8 => AbiValue::U8(number() as u8),
+        AbiType::U16 => AbiValue::U16(number() as u16),
+        AbiType::U32 => AbiValue::U32(number() as u32),
+        AbiType::U64 => AbiValue::U64(number()),
+        AbiType::B256 => AbiValue::B256([number() as u8; 32]),
+        AbiType::RawPtr => AbiValue::RawPtr(number()),
+        AbiType::Str(len) => AbiValue::Str("s".repeat(*len)),
+        AbiType::Array(element, len) => AbiValue::Array((0..*len).map(|_| sample(element, next)).collect()),
+        AbiType::Tuple(elements) => AbiValue::Tuple(elements.iter().map(|e| sample(e, next)).collect()),
+        AbiType::Struct { fields, .. } => AbiValue::Struct(fields.iter().map(|(_, f)| sample(f, next)).collect()),
+        AbiType::Enum { variants, .. } => match variants.last() {
+            Some((_, variant)) => AbiValue::Enum(variants.len() as u64 - 1, Box::new(sample(variant, next))),
+            None => AbiValue::Unit,
+        },
+    }
+}
+
+/// Samples for every input of `function`, counting up across all of them.
+pub fn sample_arguments(function: &AbiFunction) -> Vec<AbiValue> {
+    let mut next = 0;
+    function.inputs.iter().map(|(_, ty)| sample(ty, &mut next)).collect()
+}
+
+/// `function`'s arguments one after the other, as the calldata of a call to it.
+pub fn encode_arguments(encoding: Encoding, function: &AbiFunction, arguments: &[AbiValue]) -> Result<Vec<u8>, String> {
+    if arguments.len() != function.inputs.len() {
+        return Err(format!("{} takes {} arguments, got {}", function.signature(), function.inputs.len(), arguments.len()));
+    }
+    let mut bytes = vec![];
+    for ((_, ty), value) in function.inputs.iter().zip(arguments) {
+        bytes.extend(encode(encoding, ty, value)?);
+    }
+    Ok(bytes)
+}
+
+/// The inverse of `encode_arguments`; fails on leftover bytes.
+pub fn decode_arguments(encoding: Encoding, function: &AbiFunction, bytes: &[u8]) -> Result<Vec<AbiValue>, String> {
+    let mut offset = 0;
+    let mut arguments = vec![];
+    for (_, ty) in function.inputs.iter() {
+        let (value, used) = decode(encoding, ty, &bytes[offset..])?;
+        arguments.push(value);
+        offset += used;
+    }
+    match offset == bytes.len() {
+        true => Ok(arguments),
+        false => Err(format!("{} bytes left after the arguments of {}", bytes.len() - offset, function.signature())),
+    }
+}
+
+pub fn encode(encoding: Encoding, ty: &AbiType, value: &AbiValue) -> Result<Vec<u8>, String> {
+    let mismatch = || format!("{value:?} is not a {}", ty.sway_name());
+    let list = |types: &mut dyn Iterator<Item = &AbiType>, values: &[AbiValue]| -> Result<Vec<u8>, String> {
+        let types: Vec<&AbiType> = types.collect();
+        if types.len() != values.len() {
+            return Err(mismatch());
+        }
+        let mut bytes = vec![];
+        for (ty, value) in types.into_iter().zip(values) {
+            bytes.extend(encode(encoding, ty, value)?);
+        }
+        Ok(bytes)
+    };
+
+    Ok(match (ty, value) {
+        (AbiType::Unit, AbiValue::Unit) => match encoding {
+            Encoding::Legacy => vec![0; WORD_SIZE],
+            Encoding::Flat => vec![],
+        },
+        (AbiType::Bool, AbiValue::Bool(b)) => number(encoding, &[*b as u8]),
+        (AbiType::U8, AbiValue::U8(n)) => number(encoding, &n.to_be_bytes()),
+        (AbiType::U16, AbiValue::U16(n)) => number(encoding, &n.to_be_bytes()),
+        (AbiType::U32, AbiValue::U32(n)) => number(encoding, &n.to_be_bytes()),
+        (AbiType::U64, AbiValue::U64(n)) | (AbiType::RawPtr, AbiValue::RawPtr(n)) => n.to_be_bytes().to_vec(),
+        (AbiType::B256, AbiValue::B256(b)) => b.to_vec(),
+        (AbiType::Str(len), AbiValue::Str(s)) if s.len() == *len => {
+            let mut bytes = s.as_bytes().to_vec();
+            if encoding == Encoding::Legacy {
+                bytes.resize(padded(*len), 0);
+            }
+            bytes
+        }
+        (AbiType::Array(element, len), AbiValue::Array(values)) if values.len() == *len => {
+            list(&mut std::iter::repeat(element.as_ref()).take(*len), values)?
+        }
+        (AbiType::Tuple(elements), AbiValue::Tuple(values)) => list(&mut elements.iter(), values)?,
+        (AbiType::Struct { fields, .. }, AbiValue::Struct(values)) => list(&mut fields.iter().map(|(_, f)| f), values)?,
+        (AbiType::Enum { variants, .. }, AbiValue::Enum(index, value)) => {
+            let (_, variant) = variants.get(*index as usize).ok_or_else(mismatch)?;
+            let mut bytes = index.to_be_bytes().to_vec();
+            if encoding == Encoding::Legacy {
+                if units_only(variants) {
+                    return Ok(bytes);
+                }
+                bytes.resize(WORD_SIZE + legacy_variants_width(variants) - legacy_size(variant), 0);
+            }
+            bytes.extend(encode(encoding, variant, value)?);
+            bytes
+        }
+        _ => return Err(mismatch()),
+    })
+}
+
+/// Decodes one value of `ty` from the start of `bytes`, returning it and the bytes it took.
+pub fn decode(encoding: Encoding, ty: &AbiType, bytes: &[u8]) -> Result<(AbiValue, usize), String> {
+    let take = |len: usize| bytes.get(..len).ok_or(format!("{} needs {len} bytes, {} left", ty.sway_name(), bytes.len()));
+    let list = |types: &mut dyn Iterator<Item = &AbiType>| -> Result<(Vec<AbiValue>, usize), String> {
+        let mut values = vec![];
+        let mut offset = 0;
+        for ty in types {
+            let (value, used) = decode(encoding, ty, &bytes[offset..])?;
+            values.push(value);
+            offset += used;
+        }
+        Ok((values, offset))
+    };
+    // numbers smaller than a word sit at the end of a whole word in the legacy encoding.
+    let small = |len: usize| -> Result<(&[u8], usize), String> {
+        match encoding {
+            Encoding::Legacy => Ok((&take(WORD_SIZE)?[WORD_SIZE - len..], WORD_SIZE)),
+            Encoding::Flat => Ok((take(len)?, len)),
+        }
+    };
+
+    Ok(match ty {
+        AbiType::Unit => match encoding {
+            Encoding::Legacy => (AbiValue::Unit, take(WORD_SIZE).map(|_| WORD_SIZE)?),
+            Encoding::Flat => (AbiValue::Unit, 0),
+        },
+        AbiType::Bool => {
+            let (b, used) = small(1)?;
+            match b[0] {
+                0 | 1 => (AbiValue::Bool(b[0] == 1), used),
+                other => return Err(format!("{other} is not a bool")),
+            }
+        }
+        AbiType::U8 => small(1).map(|(b, used)| (AbiValue::U8(b[0]), used))?,
+        AbiType::U16 => small(2).map(|(b, used)| (AbiValue::U16(u16::from_be_bytes(b.try_into().unwrap())), used))?,
+        AbiType::U32 => small(4).map(|(b, used)| (AbiValue::U32(u32::from_be_bytes(b.try_into().unwrap())), used))?,
+        AbiType::U64 => (AbiValue::U64(word(take(WORD_SIZE)?)), WORD_SIZE),
+        AbiType::RawPtr => (AbiValue::RawPtr(word(take(WORD_SIZE)?)), WORD_SIZE),
+        AbiType::B256 => (AbiValue::B256(take(32)?.try_into().unwrap()), 32),
+        AbiType::Str(len) => {
+            let text = String::from_utf8(take(*len)?.to_vec()).map_err(|e| e.to_string())?;
+            let used = match encoding {
+                Encoding::Legacy => take(padded(*len)).map(|_| padded(*len))?,
+                Encoding::Flat => *len,
+            };
+            (AbiValue::Str(text), used)
+        }
+        AbiType::Array(element, len) => {
+            let (values, used) = list(&mut std::iter::repeat(element.as_ref()).take(*len))?;
+            (AbiValue::Array(values), used)
+        }
+        AbiType::Tuple(elements) => {
+            let (values, used) = list(&mut elements.iter())?;
+            (AbiValue::Tuple(values), used)
+        }
+        AbiType::Struct { fields, .. } => {
+            let (values, used) = list(&mut fields.iter().map(|(_, f)| f))?;
+            (AbiValue::Struct(values), used)
+        }
+        AbiType::Enum { variants, .. } => {
+            let index = word(take(WORD_SIZE)?);
+            let (_, variant) = variants
+                .get(index as usize)
+                .ok_or(format!("{} has no variant {index}", ty.sway_name()))?;
+            if encoding == Encoding::Legacy && units_only(variants) {
+                return Ok((AbiValue::Enum(index, Box::new(AbiValue::Unit)), WORD_SIZE));
+            }
+            let padding = match encoding {
+                Encoding::Legacy => legacy_variants_width(variants) - legacy_size(variant),
+                Encoding::Flat => 0,
+            };
+            let start = WORD_SIZE + padding;
+            let (value, used) = decode(encoding, variant, bytes.get(start..).unwrap_or_default())?;
+            (AbiValue::Enum(index, Box::new(value)), start + used)
+        }
+    })
+}
+
+///
+/// The golden vector of `function`: its signature and selector, and under every encoding
+/// the bytes of its sample arguments and of a sample return value.
+///
+pub fn golden_vector(function: &AbiFunction) -> Result<Value, String> {
+    let arguments = sample_arguments(function);
+    let output = sample(&function.output, &mut 0);
+
+    let mut vector = Map::new();
+    vector.insert("signature".to_string(), json!(function.signature()));
+    vector.insert("selector".to_string(), json!(format!("0x{}", hex::encode(function.selector()))));
+    for encoding in Encoding::ALL {
+        vector.insert(encoding.name().to_string(), json!({
+            "arguments": hex::encode(encode_arguments(encoding, function, &arguments)?),
+            "output": hex::encode(encode(encoding, &function.output, &output)?),
+        }));
+    }
+    Ok(Value::Object(vector))
+}
+
+/// `bytes` right-aligned in a word for the legacy encoding, as they are for the flat one.
+fn number(encoding: Encoding, bytes: &[u8]) -> Vec<u8> {
+    match encoding {
+        Encoding::Legacy => [vec![0; WORD_SIZE - bytes.len()], bytes.to_vec()].concat(),
+        Encoding::Flat => bytes.to_vec(),
+    }
+}
+
+fn word(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes.try_into().unwrap())
+}
+
+fn padded(len: usize) -> usize {
+    (len + WORD_SIZE - 1) / WORD_SIZE * WORD_SIZE
+}
+
+fn units_only(variants: &[(String, AbiType)]) -> bool {
+    variants.iter().all(|(_, v)| *v == AbiType::Unit)
+}
+
+/// Bytes a value of `ty` takes in the legacy encoding; the same for every value of it.
+fn legacy_size(ty: &AbiType) -> usize {
+    match ty {
+        AbiType::Unit | AbiType::Bool | AbiType::U8 | AbiType::U16 | AbiType::U32 | AbiType::U64 | AbiType::RawPtr => WORD_SIZE,
+        AbiType::B256 => 32,
+        AbiType::Str(len) => padded(*len),
+        AbiType::Array(element, len) => legacy_size(element) * len,
+        AbiType::Tuple(elements) => elements.iter().map(legacy_size).sum(),
+        AbiType::Struct { fields, .. } => fields.iter().map(|(_, f)| legacy_size(f)).sum(),
+        AbiType::Enum { variants, .. } => WORD_SIZE + legacy_variants_width(variants),
+    }
+}
+
+/// Width of the widest variant, which every variant is right-aligned in. An enum of
+/// units only is just its discriminant.
+fn legacy_variants_width(variants: &[(String, AbiType)]) -> usize {
+    match units_only(variants) {
+        true => 0,
+        false => variants.iter().map(|(_, v)| legacy_size(v)).max().unwrap_or(0),
+    }
+}
@@ -0,0 +1,88 @@
+use fuels::{
+    prelude::*,
+    types::Identity,
+};
+
+use crate::TargetContract;
+
+/// Where a `balances` entry written under some key ended up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Landing {
+    /// Under the key it was written with.
+    Expected,
+    /// Only under other keys, see `misplaced_keys`.
+    Elsewhere(Vec<Identity>),
+    /// Under none of the keys tried.
+    Missing,
+}
+
+///
+/// Keys a wrongly encoded `owner` plausibly turns into: its bytes as the other Identity
+/// variant, for a wrong discriminant, and zero bytes as either variant, for a key read
+/// from the wrong memory.
+///
+pub fn misplaced_keys(owner: &Identity) -> Vec<Identity> {
+    let (bytes, other) = match owner {
+        Identity::Address(address) => (**address, Identity::ContractId(ContractId::from(**address))),
+        Identity::ContractId(contract_id) => (**contract_id, Identity::Address(Address::from(**contract_id))),
+    };
+    let zero = [0u8; 32];
+    let mut keys = vec![other];
+    if bytes != zero {
+        keys.push(Identity::Address(Address::from(zero)));
+        keys.push(Identity::ContractId(ContractId::from(zero)));
+    }
+    keys
+}
+
+pub async fn balance(target: &TargetContract<WalletUnlocked>, owner: &Identity) -> Result<u64> {
+    Ok(target.methods().get_balance(*owner).simulate().await?.value)
+}
+
+///
+/// Whether `amount` is the balance of `owner`, and if not, which of `misplaced_keys(owner)`
+/// hold it instead.
+///
+pub async fn balance_landing(target: &TargetContract<WalletUnlocked>, owner: &Identity, amount: u64) -> Result<Landing> {
+    if balance(target, owner).await? == amount {
+        return Ok(Landing::Expected);
+    }
+    let mut found = vec![];
+    for key in misplaced_keys(owner) {
+        if balance(target, &key).await? == amount {
+            found.push(key);
+        }
+    }
+    Ok(match found.is_empty() {
+        true => Landing::Missing,
+        false => Landing::Elsewhere(found),
+    })
+}
+
+/// Every `entries` element, in order.
+pub async fn entries(target: &TargetContract<WalletUnlocked>) -> Result<Vec<u64>> {
+    let len = target.methods().entries_len().simulate().await?.value;
+    let mut entries = vec![];
+    for index in 0..len {
+        match target.methods().get_entry(index).simulate().await?.value {
+            Some(entry) => entries.push(entry),
+            None => return Err(Error::InvalidData(format!("entries has no element {index} of {len}"))),
+        }
+    }
+    Ok(entries)
+}
+
+/// The indexes where `found` differs from `expected`, and any difference in length.
+pub fn entry_mismatches(expected: &[u64], found: &[u64]) -> Vec<String> {
+    let mut mismatches: Vec<String> = expected
+        .iter()
+        .zip(found)
+        .enumerate()
+        .filter(|(_, (e, f))| e != f)
+        .map(|(index, (e, f))| format!("entries[{index}]: expected {e}, found {f}"))
+        .collect();
+    if expected.len() != found.len() {
+        mismatches.push(format!("{} entries, expected {}", found.len(), expected.len()));
+    }
+    mismatches
+}
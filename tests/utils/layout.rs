@@ -0,0 +1,234 @@
+use std::fmt::Write;
+
+use crate::utils::abi::{AbiFunction, AbiType};
+
+/// Function or struct / enum name the `print_layout` test shows, instead of every setter.
+pub const LAYOUT_ENV: &str = "LLCALL_LAYOUT";
+
+const WORD_SIZE: usize = 8;
+
+/// One primitive value of a layout, with the padding that fills its slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+    /// Where the value sits in the type, e.g. `x.b[1]`, `a.0` or `error::TooLarge`.
+    pub path: String,
+    pub sway_type: String,
+    /// Start of the slot, padding included.
+    pub offset: usize,
+    /// Numbers and bools are right-aligned in their word.
+    pub padding_before: usize,
+    pub size: usize,
+    /// Strings are left-aligned and padded up to a whole word.
+    pub padding_after: usize,
+    /// The value is an address, the data it points to is stored elsewhere.
+    pub pointer: bool,
+    /// One of the variants of an enum: variants share the bytes after the discriminant.
+    pub variant: bool,
+}
+
+impl Slot {
+    fn primitive(path: &str, ty: &AbiType, offset: usize) -> Slot {
+        let (padding_before, size, padding_after) = match ty {
+            AbiType::Unit => (WORD_SIZE, 0, 0),
+            AbiType::Bool | AbiType::U8 => (7, 1, 0),
+            AbiType::U16 => (6, 2, 0),
+            AbiType::U32 => (4, 4, 0),
+            AbiType::B256 => (0, 32, 0),
+            AbiType::Str(len) => (0, *len, padded(*len) - len),
+            _ => (0, WORD_SIZE, 0),
+        };
+        Slot {
+            path: path.to_string(),
+            sway_type: ty.sway_name(),
+            offset,
+            padding_before,
+            size,
+            padding_after,
+            pointer: matches!(ty, AbiType::RawPtr),
+            variant: false,
+        }
+    }
+
+    /// The bytes holding the value itself.
+    pub fn value_range(&self) -> std::ops::Range<usize> {
+        let start = self.offset + self.padding_before;
+        start..start + self.size
+    }
+
+    pub fn end(&self) -> usize {
+        self.value_range().end + self.padding_after
+    }
+}
+
+/// How a type, or the arguments of a function, sit in VM memory under the legacy encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub sway_type: String,
+    pub size: usize,
+    /// Passed in param2 as a pointer to `size` bytes, rather than by value.
+    pub by_pointer: bool,
+    pub slots: Vec<Slot>,
+}
+
+/// The layout of one value of `ty`, its slots named from `path`.
+pub fn of_type(path: &str, ty: &AbiType) -> Layout {
+    let mut slots = vec![];
+    let size = lay_out(path, ty, 0, &mut slots);
+    Layout {
+        sway_type: ty.sway_name(),
+        size,
+        by_pointer: !ty.is_single_word(),
+        slots,
+    }
+}
+
+///
+/// The layout of `function`'s arguments as `calldata!` encodes them: one after the other,
+/// each named after its input. Only a single argument that fits in a word is passed by value.
+///
+pub fn of_arguments(function: &AbiFunction) -> Layout {
+    let mut slots = vec![];
+    let mut size = 0;
+    for (name, ty) in function.inputs.iter() {
+        size += lay_out(name, ty, size, &mut slots);
+    }
+    Layout {
+        sway_type: function.signature(),
+        size,
+        by_pointer: !(function.inputs.len() == 1 && function.inputs[0].1.is_single_word()),
+        slots,
+    }
+}
+
+/// Appends the slots of `ty` at `offset` and returns how many bytes it takes.
+fn lay_out(path: &str, ty: &AbiType, offset: usize, slots: &mut Vec<Slot>) -> usize {
+    let mut size = 0;
+    match ty {
+        AbiType::Array(element, len) => {
+            for i in 0..*len {
+                size += lay_out(&format!("{path}[{i}]"), element, offset + size, slots);
+            }
+        }
+        AbiType::Tuple(elements) => {
+            for (i, element) in elements.iter().enumerate() {
+                size += lay_out(&format!("{path}.{i}"), element, offset + size, slots);
+            }
+        }
+        AbiType::Struct { fields, .. } => {
+            for (name, field) in fields.iter() {
+                size += lay_out(&format!("{path}.{name}"), field, offset + size, slots);
+            }
+        }
+        AbiType::Enum { variants, .. } => {
+            slots.push(Slot::primitive(&format!("{path} (discriminant)"), &AbiType::U64, offset));
+            // an enum of units only is just its discriminant.
+            if variants.iter().all(|(_, v)| *v == AbiType::Unit) {
+                return WORD_SIZE;
+            }
+            // every variant is right-aligned in the width of the widest one.
+            let width = variants.iter().map(|(_, v)| encoded_size(v)).max().unwrap_or(0);
+            for (name, variant) in variants.iter() {
+                let start = slots.len();
+                let padding = width - encoded_size(variant);
+                lay_out(&format!("{path}::{name}"), variant, offset + WORD_SIZE + padding, slots);
+                for slot in slots[start..].iter_mut() {
+                    slot.variant = true;
+                }
+                if let Some(first) = slots.get_mut(start) {
+                    first.offset -= padding;
+                    first.padding_before += padding;
+                }
+            }
+            size = WORD_SIZE + width;
+        }
+        _ => {
+            let slot = Slot::primitive(path, ty, offset);
+            size = slot.end() - offset;
+            slots.push(slot);
+        }
+    }
+    size
+}
+
+/// Bytes `ty` takes once encoded.
+pub fn encoded_size(ty: &AbiType) -> usize {
+    lay_out("", ty, 0, &mut vec![])
+}
+
+fn padded(len: usize) -> usize {
+    (len + WORD_SIZE - 1) / WORD_SIZE * WORD_SIZE
+}
+
+///
+/// Where `bytes` disagree with `layout`: a different length, or padding that isn't zero.
+/// Enum variants are not checked, only one of them is actually in the bytes.
+///
+pub fn mismatches(layout: &Layout, bytes: &[u8]) -> Vec<String> {
+    let mut found = vec![];
+    if bytes.len() != layout.size {
+        found.push(format!("{} bytes encoded, the layout of {} takes {}", bytes.len(), layout.sway_type, layout.size));
+    }
+    for slot in layout.slots.iter().filter(|s| !s.variant && s.end() <= bytes.len()) {
+        let value = slot.value_range();
+        let mut padding = bytes[slot.offset..value.start].iter().chain(&bytes[value.end..slot.end()]);
+        if padding.any(|b| *b != 0) {
+            found.push(format!("{}: padding around bytes {}..{} is not zero", slot.path, value.start, value.end));
+        }
+    }
+    found
+}
+
+///
+/// The layout as a table, one slot per line. With `bytes`, each line ends with the bytes
+/// found at that slot, padding in brackets, followed by every mismatch.
+///
+pub fn render(layout: &Layout, bytes: Option<&[u8]>) -> String {
+    let passed = if layout.by_pointer { "passed by pointer" } else { "passed by value" };
+    let mut out = format!("{}: {} bytes, {passed}\n", layout.sway_type, layout.size);
+    writeln!(out, "{:>6} {:>4} {:>5}  {:<24} {:<12} bytes", "offset", "size", "pad", "field", "type").unwrap();
+
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{b:02x}")).collect::<Vec<_>>().join(" ");
+    for slot in layout.slots.iter() {
+        let mut flags = String::new();
+        if slot.pointer {
+            flags.push_str(" -> pointer");
+        }
+        if slot.variant {
+            flags.push_str(" (variant)");
+        }
+        let shown = match bytes {
+            Some(bytes) if slot.end() <= bytes.len() => {
+                let value = slot.value_range();
+                let padding_before = &bytes[slot.offset..value.start];
+                let padding_after = &bytes[value.end..slot.end()];
+                let mut shown = String::new();
+                if !padding_before.is_empty() {
+                    write!(shown, "[{}] ", hex(padding_before)).unwrap();
+                }
+                shown.push_str(&hex(&bytes[value.clone()]));
+                if !padding_after.is_empty() {
+                    write!(shown, " [{}]", hex(padding_after)).unwrap();
+                }
+                shown
+            }
+            Some(_) => "(missing)".to_string(),
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "{:>6} {:>4} {:>5}  {:<24} {:<12} {shown}{flags}",
+            slot.offset,
+            slot.size,
+            format!("{}+{}", slot.padding_before, slot.padding_after),
+            slot.path,
+            slot.sway_type,
+        )
+        .unwrap();
+    }
+    if let Some(bytes) = bytes {
+        for mismatch in mismatches(layout, bytes) {
+            writeln!(out, "❌ {mismatch}").unwrap();
+        }
+    }
+    out
+}
@@ -0,0 +1,343 @@
+use std::fmt;
+
+use fuels::{
+    prelude::*,
+    core::traits::{Parameterize, Tokenizable},
+    programs::{call_response::FuelCallResponse, contract::ContractCallHandler},
+    tx::{PanicReason, Receipt},
+    types::errors::Error,
+};
+
+use crate::{utils::simulate, CallerContract};
+
+/// Extra gas, in percent of the dry-run estimate, forwarded on top of it.
+pub const DEFAULT_FORWARD_GAS_MARGIN_PERCENT: u64 = 20;
+
+/// TargetContract's fallback reverts with this for selectors it doesn't implement.
+pub const UNKNOWN_SELECTOR_REVERT_CODE: u64 = 0xbad5e1ec;
+/// Sway's `require()` reverts with this after logging its message.
+pub const FAILED_REQUIRE_SIGNAL: u64 = 0xffff_ffff_ffff_0000;
+
+/// Gas the SDK forwards to CallerContract itself when call params are set.
+const DEFAULT_CALL_PARAMS_GAS: u64 = 1_000_000;
+
+/// Tx gas limit the dry runs of `plan_gas` get, far above what any TargetContract call needs.
+const PROBE_GAS_LIMIT: u64 = 1_000_000;
+
+/// How CallerContract builds the call frame for a low-level call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LowLevelPath {
+    /// std's `call_with_function_selector`, via `call_low_level_call`.
+    #[default]
+    Std,
+    /// The frame written by hand and called with explicit asm, via `call_low_level_call_asm`.
+    Asm,
+}
+
+/// A call to a target contract made through CallerContract's `call_with_function_selector`.
+#[derive(Debug, Clone)]
+pub struct LowLevelCall {
+    pub target: ContractId,
+    pub function_selector: Vec<u8>,
+    pub calldata: Vec<u8>,
+    pub single_value_type_arg: bool,
+    /// Only used by `low_level_call_handler`; the gas and coin variants always go through std.
+    pub path: LowLevelPath,
+}
+
+impl LowLevelCall {
+    pub fn with_path(mut self, path: LowLevelPath) -> Self {
+        self.path = path;
+        self
+    }
+
+    ///
+    /// The frame `call_low_level_call_asm` should build for this call, for comparing with
+    /// the `CallFrame` it logs. `param2` is only known up front for single value arguments,
+    /// otherwise it is a pointer into CallerContract's memory.
+    ///
+    pub fn expected_frame(&self) -> (ContractId, u64, Option<u64>) {
+        let word = |bytes: &[u8]| bytes.get(..8).map(|w| u64::from_be_bytes(w.try_into().unwrap()));
+        let param1 = word(&self.function_selector).unwrap_or(0);
+        let param2 = if self.single_value_type_arg { word(&self.calldata) } else { None };
+        (self.target, param1, param2)
+    }
+}
+
+#[derive(Debug)]
+pub enum LowLevelCallError {
+    /// The VM ran out of gas. `contract_id` is the contract whose frame panicked,
+    /// `None` when the outer script itself ran out.
+    OutOfGas {
+        contract_id: Option<ContractId>,
+        forwarded_gas: u64,
+        tx_gas_limit: u64,
+        receipts: Vec<Receipt>,
+    },
+    /// The transaction reverted for any other reason.
+    Reverted {
+        reason: String,
+        revert_id: u64,
+        receipts: Vec<Receipt>,
+    },
+    /// The SDK failed before or after execution.
+    Sdk(Error),
+}
+
+impl fmt::Display for LowLevelCallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LowLevelCallError::OutOfGas { contract_id: Some(id), forwarded_gas, .. } => {
+                write!(f, "out of gas in contract 0x{id} (forwarded {forwarded_gas} gas)")
+            }
+            LowLevelCallError::OutOfGas { contract_id: None, tx_gas_limit, .. } => {
+                write!(f, "out of gas in script (tx gas limit {tx_gas_limit})")
+            }
+            LowLevelCallError::Reverted { reason, revert_id, .. } => {
+                write!(f, "reverted: {reason} (revert id {revert_id})")
+            }
+            LowLevelCallError::Sdk(error) => write!(f, "sdk error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LowLevelCallError {}
+
+/// Gas budget for one low-level call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GasPlan {
+    /// Gas CallerContract forwards to the target.
+    pub forwarded_gas: u64,
+    /// Gas limit of the outer transaction.
+    pub tx_gas_limit: u64,
+}
+
+///
+/// Dry-runs `probe` (the same call made directly to the target) and returns the gas the
+/// target's own frame needed plus `margin_percent`.
+///
+/// The script forwards all it has left to the target, so the Call receipt's gas is the
+/// probe's gas limit minus what the script spent before calling. That part is taken off the
+/// probe's total, leaving the target's share and the few instructions the script runs after.
+///
+pub async fn estimate_forward_gas<D>(
+    probe: ContractCallHandler<WalletUnlocked, D>,
+    margin_percent: u64,
+) -> std::result::Result<u64, LowLevelCallError>
+where
+    D: Tokenizable + Parameterize + std::fmt::Debug,
+{
+    let target = ContractId::from(&probe.contract_call.contract_id);
+    let response = probe
+        .tx_params(TxParameters::default().set_gas_limit(PROBE_GAS_LIMIT))
+        .simulate()
+        .await
+        .map_err(LowLevelCallError::from)?;
+    let call_gas = response
+        .receipts
+        .iter()
+        .find_map(|r| match r {
+            Receipt::Call { to, gas, .. } if *to == target => Some(*gas),
+            _ => None,
+        })
+        .ok_or_else(|| LowLevelCallError::Sdk(Error::InvalidData(format!("the probe never called 0x{target}"))))?;
+    let script_overhead = PROBE_GAS_LIMIT.saturating_sub(call_gas);
+    Ok(with_margin(response.gas_used.saturating_sub(script_overhead), margin_percent))
+}
+
+///
+/// Estimates the gas the target needs from `probe`, then dry-runs the low-level call with
+/// that much forwarded. The tx gas limit is the dry run's total plus `margin_percent`:
+/// the total already holds whatever of the forwarded gas the target used.
+///
+pub async fn plan_gas<D>(
+    caller: &CallerContract<WalletUnlocked>,
+    call: &LowLevelCall,
+    probe: ContractCallHandler<WalletUnlocked, D>,
+    margin_percent: u64,
+) -> std::result::Result<GasPlan, LowLevelCallError>
+where
+    D: Tokenizable + Parameterize + std::fmt::Debug,
+{
+    let forwarded_gas = estimate_forward_gas(probe, margin_percent).await?;
+
+    let response = handler(caller, call, forwarded_gas)
+        .tx_params(TxParameters::default().set_gas_limit(PROBE_GAS_LIMIT))
+        .simulate()
+        .await
+        .map_err(|e| classify(e, forwarded_gas, PROBE_GAS_LIMIT))?;
+
+    Ok(GasPlan {
+        forwarded_gas,
+        tx_gas_limit: with_margin(response.gas_used, margin_percent),
+    })
+}
+
+///
+/// Calls `call_low_level_call_with_gas` with the gas budget in `plan`.
+/// Running out of gas is reported as `LowLevelCallError::OutOfGas`, not as a generic revert.
+///
+pub async fn call_with_gas_plan(
+    caller: &CallerContract<WalletUnlocked>,
+    call: &LowLevelCall,
+    plan: GasPlan,
+) -> std::result::Result<FuelCallResponse<()>, LowLevelCallError> {
+    gas_plan_handler(caller, call, plan)
+        .call()
+        .await
+        .map_err(|e| classify(e, plan.forwarded_gas, plan.tx_gas_limit))
+}
+
+/// `call_low_level_call_with_gas` with the gas budget in `plan`, ready to send.
+pub fn gas_plan_handler(
+    caller: &CallerContract<WalletUnlocked>,
+    call: &LowLevelCall,
+    plan: GasPlan,
+) -> ContractCallHandler<WalletUnlocked, ()> {
+    handler(caller, call, plan.forwarded_gas).tx_params(TxParameters::default().set_gas_limit(plan.tx_gas_limit))
+}
+
+///
+/// `call_low_level_call`, or `call_low_level_call_asm` for `LowLevelPath::Asm`, with the
+/// fixed 100_000 forwarded gas, ready to `.call()` or `.simulate()`.
+///
+pub fn low_level_call_handler(
+    caller: &CallerContract<WalletUnlocked>,
+    call: &LowLevelCall,
+) -> ContractCallHandler<WalletUnlocked, ()> {
+    let methods = caller.methods();
+    let selector = Bytes(call.function_selector.clone());
+    let calldata = Bytes(call.calldata.clone());
+    let handler = match call.path {
+        LowLevelPath::Std => methods.call_low_level_call(call.target, selector, calldata, call.single_value_type_arg),
+        LowLevelPath::Asm => methods.call_low_level_call_asm(call.target, selector, calldata, call.single_value_type_arg),
+    };
+    handler.set_contract_ids(&[Bech32ContractId::from(call.target)])
+}
+
+///
+/// `call_low_level_call_with_coins`: sends `amount` of `asset_id` to CallerContract with the
+/// call, and CallerContract forwards all of it to the target.
+///
+pub fn low_level_call_with_coins_handler(
+    caller: &CallerContract<WalletUnlocked>,
+    call: &LowLevelCall,
+    amount: u64,
+    asset_id: AssetId,
+) -> Result<ContractCallHandler<WalletUnlocked, ()>> {
+    caller
+        .methods()
+        .call_low_level_call_with_coins(
+            call.target,
+            Bytes(call.function_selector.clone()),
+            Bytes(call.calldata.clone()),
+            call.single_value_type_arg,
+            amount,
+            ContractId::new(*asset_id),
+        )
+        .set_contract_ids(&[Bech32ContractId::from(call.target)])
+        .call_params(CallParameters::new(amount, asset_id, DEFAULT_CALL_PARAMS_GAS))
+}
+
+fn handler(
+    caller: &CallerContract<WalletUnlocked>,
+    call: &LowLevelCall,
+    forwarded_gas: u64,
+) -> ContractCallHandler<WalletUnlocked, ()> {
+    caller
+        .methods()
+        .call_low_level_call_with_gas(
+            call.target,
+            Bytes(call.function_selector.clone()),
+            Bytes(call.calldata.clone()),
+            call.single_value_type_arg,
+            forwarded_gas,
+        )
+        .set_contract_ids(&[Bech32ContractId::from(call.target)])
+}
+
+///
+/// Sends `handler`, or dry-runs it under LLCALL_SIMULATE, and returns its receipts, whether
+/// it succeeded or reverted.
+///
+pub async fn call_receipts(handler: ContractCallHandler<WalletUnlocked, ()>) -> Result<Vec<Receipt>> {
+    match simulate::simulation_enabled() {
+        true => receipts_or_revert(handler.simulate().await),
+        false => committed_receipts(handler).await,
+    }
+}
+
+/// `call_receipts` that always commits, for tests that count what the call left behind.
+pub async fn committed_receipts(handler: ContractCallHandler<WalletUnlocked, ()>) -> Result<Vec<Receipt>> {
+    receipts_or_revert(handler.call().await)
+}
+
+fn receipts_or_revert(response: Result<FuelCallResponse<()>>) -> Result<Vec<Receipt>> {
+    match response {
+        Ok(response) => Ok(response.receipts),
+        Err(Error::RevertTransactionError { receipts, .. }) => Ok(receipts),
+        Err(error) => Err(error),
+    }
+}
+
+/// Whether `receipts` show a call into `contract_id`.
+pub fn called(receipts: &[Receipt], contract_id: ContractId) -> bool {
+    receipts.iter().any(|r| matches!(r, Receipt::Call { to, .. } if *to == contract_id))
+}
+
+/// Code `contract_id` reverted with, if it did.
+pub fn revert_code(receipts: &[Receipt], contract_id: ContractId) -> Option<u64> {
+    receipts.iter().find_map(|r| match r {
+        Receipt::Revert { id, ra, .. } if *id == contract_id => Some(*ra),
+        _ => None,
+    })
+}
+
+/// Values `contract_id` logged with a single word `log()`.
+pub fn logged_words(receipts: &[Receipt], contract_id: ContractId) -> Vec<u64> {
+    receipts
+        .iter()
+        .filter_map(|r| match r {
+            Receipt::Log { id, ra, .. } if *id == contract_id => Some(*ra),
+            _ => None,
+        })
+        .collect()
+}
+
+fn with_margin(gas: u64, margin_percent: u64) -> u64 {
+    gas + gas * margin_percent / 100
+}
+
+fn classify(error: Error, forwarded_gas: u64, tx_gas_limit: u64) -> LowLevelCallError {
+    match error {
+        Error::RevertTransactionError { reason, revert_id, receipts } => {
+            match out_of_gas_panic(&receipts) {
+                Some(contract_id) => LowLevelCallError::OutOfGas {
+                    contract_id,
+                    forwarded_gas,
+                    tx_gas_limit,
+                    receipts,
+                },
+                None => LowLevelCallError::Reverted { reason, revert_id, receipts },
+            }
+        }
+        error => LowLevelCallError::Sdk(error),
+    }
+}
+
+/// `Some(contract)` if the receipts end in an OutOfGas panic, with the panicking contract
+/// (`None` inside the script).
+fn out_of_gas_panic(receipts: &[Receipt]) -> Option<Option<ContractId>> {
+    receipts.iter().find_map(|r| match r {
+        Receipt::Panic { id, reason, .. } if *reason.reason() == PanicReason::OutOfGas => {
+            Some((*id != ContractId::zeroed()).then_some(*id))
+        }
+        _ => None,
+    })
+}
+
+impl From<Error> for LowLevelCallError {
+    fn from(error: Error) -> Self {
+        classify(error, 0, 0)
+    }
+}
@@ -0,0 +1,103 @@
+use std::fs;
+use std::io;
+
+use fuels::{
+    prelude::LoadConfiguration,
+    types::ContractId,
+};
+use serde_json::{json, Value};
+
+use crate::utils::configurables::TargetConfigurables;
+
+/// Written next to caller_cid.txt / target_cid.txt by the deploy helpers.
+pub const DEPLOYMENT_MANIFEST: &str = "./deployments.json";
+
+/// One deployed contract, with everything needed to deploy it again at the same id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeployedContract {
+    pub label: String,
+    pub binary_path: String,
+    pub contract_id: ContractId,
+    pub salt: [u8; 32],
+    /// Overrides of TargetContract's configurables, `None` for every other contract.
+    pub configurables: Option<TargetConfigurables>,
+}
+
+impl DeployedContract {
+    /// What to deploy the binary with again: the salt, and the overrides if it has any.
+    pub fn load_configuration(&self) -> LoadConfiguration {
+        match &self.configurables {
+            Some(configurables) => configurables.load_configuration(self.salt),
+            None => LoadConfiguration::default().set_salt(self.salt),
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "label": self.label,
+            "binary_path": self.binary_path,
+            "contract_id": format!("0x{}", self.contract_id),
+            "salt": format!("0x{}", hex::encode(self.salt)),
+            "configurables": self.configurables.as_ref().map(TargetConfigurables::to_json),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> io::Result<Self> {
+        let field = |name: &str| {
+            value[name]
+                .as_str()
+                .ok_or_else(|| invalid(format!("deployment entry without `{name}`")))
+        };
+
+        let contract_id = field("contract_id")?
+            .parse()
+            .map_err(|_| invalid("invalid contract_id in deployment entry".to_string()))?;
+        let salt = hex::decode(field("salt")?.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| invalid("invalid salt in deployment entry".to_string()))?;
+        let configurables = match value.get("configurables") {
+            Some(configurables) if !configurables.is_null() => Some(TargetConfigurables::from_json(configurables)?),
+            _ => None,
+        };
+
+        Ok(DeployedContract {
+            label: field("label")?.to_string(),
+            binary_path: field("binary_path")?.to_string(),
+            contract_id,
+            salt,
+            configurables,
+        })
+    }
+}
+
+pub fn read_manifest(path: &str) -> io::Result<Vec<DeployedContract>> {
+    let contents = fs::read_to_string(path)?;
+    let value: Value = serde_json::from_str(&contents).map_err(|e| invalid(e.to_string()))?;
+    entries_from_json(&value)
+}
+
+pub fn entries_from_json(value: &Value) -> io::Result<Vec<DeployedContract>> {
+    value
+        .as_array()
+        .ok_or_else(|| invalid("deployment manifest is not a list".to_string()))?
+        .iter()
+        .map(DeployedContract::from_json)
+        .collect()
+}
+
+///
+/// Adds `entry` to the manifest, replacing any earlier entry with the same label.
+///
+pub fn record_deployment(path: &str, entry: DeployedContract) -> io::Result<()> {
+    let mut entries = read_manifest(path).unwrap_or_default();
+    entries.retain(|e| e.label != entry.label);
+    entries.push(entry);
+
+    let value = Value::Array(entries.iter().map(DeployedContract::to_json).collect());
+    fs::write(path, serde_json::to_string_pretty(&value)?)
+}
+
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,21 @@
+pub mod abi;
+pub mod bench;
+pub mod codegen;
+pub mod config;
+pub mod configurables;
+pub mod context;
+pub mod encoding;
+pub mod keyed;
+pub mod layout;
+pub mod llcall;
+pub mod manifest;
+pub mod nest;
+pub mod record;
+pub mod report;
+pub mod results;
+pub mod revert;
+pub mod selectors;
+pub mod simulate;
+pub mod stress;
+pub mod trace;
+pub mod wallets;
@@ -0,0 +1,107 @@
+use fuels::{
+    prelude::*,
+    programs::contract::ContractCallHandler,
+    tx::Receipt,
+};
+
+use crate::{
+    utils::abi,
+    utils::selectors::SelectorRegistry,
+    utils::trace,
+    CallerContract,
+};
+
+/// One call in a transaction, with how deep in the call stack it ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hop {
+    /// 1 for a call made by the script, 2 for a call made from that contract, and so on.
+    pub depth: usize,
+    pub from: ContractId,
+    pub to: ContractId,
+    /// Gas forwarded with the call.
+    pub gas: u64,
+    /// `to` was already on the call stack when it was called again.
+    pub reentrant: bool,
+}
+
+///
+/// Every call in `receipts` in execution order, with its depth, flattened from the call
+/// tree `trace::build` rebuilds.
+///
+pub fn depth_trace(receipts: &[Receipt]) -> Vec<Hop> {
+    let tree = trace::build(receipts, None, &[], &SelectorRegistry::default());
+    // the contracts from the script down to the current node's caller.
+    let mut stack: Vec<ContractId> = vec![];
+    let mut hops = vec![];
+    for (depth, node) in tree.walk() {
+        stack.truncate(depth);
+        if depth > 0 {
+            hops.push(Hop {
+                depth,
+                from: stack[depth - 1],
+                to: node.contract_id,
+                gas: node.gas_forwarded,
+                reentrant: stack[1..].contains(&node.contract_id),
+            });
+        }
+        stack.push(node.contract_id);
+    }
+    hops
+}
+
+/// Prints one line per hop, naming contracts by `labels` where known.
+pub fn print_trace(hops: &[Hop], labels: &[(ContractId, &str)]) {
+    let name = |id: &ContractId| {
+        labels
+            .iter()
+            .find(|(l, _)| l == id)
+            .map(|(_, label)| label.to_string())
+            .unwrap_or_else(|| format!("0x{id}"))
+    };
+    for hop in hops {
+        println!(
+            "{}depth {} -> {} (gas {}){}",
+            "  ".repeat(hop.depth - 1),
+            hop.depth,
+            name(&hop.to),
+            hop.gas,
+            if hop.reentrant { " 🔁 reentrant" } else { "" }
+        );
+    }
+}
+
+///
+/// Points `caller`'s forward() at `next`'s `selector`. `forward_gas` 0 forwards all gas.
+/// Only the first `max_calls` nested entries of forward() call next, the one after stops.
+///
+pub async fn configure(
+    caller: &CallerContract<WalletUnlocked>,
+    next: ContractId,
+    selector: Vec<u8>,
+    forward_gas: u64,
+    max_calls: u64,
+) -> Result<()> {
+    caller
+        .methods()
+        .configure_forward(next, abi::selector_word(&selector), forward_gas, max_calls)
+        .call()
+        .await?;
+    Ok(())
+}
+
+///
+/// `caller.forward(value)` with every contract the chain may reach as an input: the
+/// callers in `chain` and the `target` the last of them calls.
+///
+pub fn forward_handler(
+    caller: &CallerContract<WalletUnlocked>,
+    value: u64,
+    chain: &[ContractId],
+    target: ContractId,
+) -> ContractCallHandler<WalletUnlocked, ()> {
+    let mut ids: Vec<Bech32ContractId> = chain.iter().map(|id| Bech32ContractId::from(*id)).collect();
+    if !chain.contains(&target) {
+        ids.push(Bech32ContractId::from(target));
+    }
+    caller.methods().forward(value).set_contract_ids(&ids)
+}
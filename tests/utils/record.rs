@@ -0,0 +1,271 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use fuel_types::bytes::{Deserializable, SerializableVec};
+use fuels::{
+    prelude::*,
+    tx::{Receipt, Transaction as FuelTransaction},
+    types::{transaction::ScriptTransaction, Bits256},
+};
+use serde_json::{json, Value};
+
+use crate::{
+    utils::manifest::{self, DeployedContract},
+    utils::simulate::{self, TargetStorage},
+    TargetContract,
+};
+
+/// When set, every call made through `send_then_read` is recorded under this directory.
+pub const RECORD_DIR_ENV: &str = "LLCALL_RECORD_DIR";
+/// Recording directory replayed by the `replay_recording` test.
+pub const REPLAY_ENV: &str = "LLCALL_REPLAY";
+
+const TX_FILE: &str = "tx.bin";
+const META_FILE: &str = "meta.json";
+
+pub fn record_dir() -> Option<PathBuf> {
+    std::env::var(RECORD_DIR_ENV).ok().map(PathBuf::from)
+}
+
+/// The thread libtest runs every test on with `--test-threads=1`.
+const MAIN_THREAD: &str = "main";
+
+///
+/// Name of the running test; libtest names each test thread after its test. Empty on the
+/// main thread, where no test can be told from another, see `unnamed_test`.
+///
+pub fn current_test_name() -> String {
+    match std::thread::current().name() {
+        Some(name) if name != MAIN_THREAD => name.replace("::", "_"),
+        _ => String::new(),
+    }
+}
+
+/// Why nothing is written for a test `current_test_name` could not name.
+pub fn unnamed_test() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "the test runs on the main thread (--test-threads=1) and can't be named, run it with more test threads",
+    )
+}
+
+/// One submitted transaction and what it did.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub test: String,
+    /// The decoded arguments the test meant to send, e.g. `set2(10, 11)`.
+    pub intent: String,
+    pub deployments: Vec<DeployedContract>,
+    pub tx_bytes: Vec<u8>,
+    pub receipts: Vec<String>,
+    pub storage: TargetStorage,
+}
+
+pub fn tx_bytes(tx: &ScriptTransaction) -> Vec<u8> {
+    let mut tx: FuelTransaction = tx.clone().into();
+    tx.to_bytes()
+}
+
+///
+/// Writes `recording` to `<dir>/<test>-<n>/` as tx.bin and meta.json. Refuses a recording
+/// without a test name, which `sequences` would merge with every other one.
+///
+pub fn save(dir: &Path, recording: &Recording) -> io::Result<PathBuf> {
+    if recording.test.is_empty() {
+        return Err(unnamed_test());
+    }
+    let mut n = 0;
+    let entry = loop {
+        let candidate = dir.join(format!("{}-{}", recording.test, n));
+        if !candidate.exists() {
+            break candidate;
+        }
+        n += 1;
+    };
+    fs::create_dir_all(&entry)?;
+
+    let meta = json!({
+        "test": recording.test,
+        "intent": recording.intent,
+        "deployments": recording.deployments.iter().map(DeployedContract::to_json).collect::<Vec<_>>(),
+        "receipts": recording.receipts,
+        "storage": storage_to_json(&recording.storage),
+    });
+    fs::write(entry.join(TX_FILE), &recording.tx_bytes)?;
+    fs::write(entry.join(META_FILE), serde_json::to_string_pretty(&meta)?)?;
+    println!("📼 recorded {} to {}", recording.intent, entry.display());
+    Ok(entry)
+}
+
+pub fn load(entry: &Path) -> io::Result<Recording> {
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+    let meta: Value = serde_json::from_str(&fs::read_to_string(entry.join(META_FILE))?)
+        .map_err(|e| invalid(&e.to_string()))?;
+
+    Ok(Recording {
+        test: meta["test"].as_str().unwrap_or_default().to_string(),
+        intent: meta["intent"].as_str().unwrap_or_default().to_string(),
+        deployments: manifest::entries_from_json(&meta["deployments"])?,
+        tx_bytes: fs::read(entry.join(TX_FILE))?,
+        receipts: meta["receipts"]
+            .as_array()
+            .ok_or_else(|| invalid("recording without receipts"))?
+            .iter()
+            .map(|r| r.as_str().unwrap_or_default().to_string())
+            .collect(),
+        storage: storage_from_json(&meta["storage"]).ok_or_else(|| invalid("recording without storage"))?,
+    })
+}
+
+pub fn receipt_lines(receipts: &[Receipt]) -> Vec<String> {
+    receipts.iter().map(|r| format!("{r:?}")).collect()
+}
+
+/// Differences found by replaying a recording.
+#[derive(Debug, Default)]
+pub struct ReplayDiff {
+    pub receipts: Vec<String>,
+    pub storage: Vec<String>,
+}
+
+impl ReplayDiff {
+    pub fn is_empty(&self) -> bool {
+        self.receipts.is_empty() && self.storage.is_empty()
+    }
+}
+
+///
+/// The recordings under `dir`, one sequence per test in the order the test sent them.
+///
+pub fn sequences(dir: &Path) -> io::Result<Vec<Vec<PathBuf>>> {
+    let mut entries: Vec<(String, usize, PathBuf)> = vec![];
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if !path.join(TX_FILE).exists() {
+            continue;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let (test, n) = match name.rsplit_once('-').map(|(t, n)| (t, n.parse::<usize>())) {
+            Some((test, Ok(n))) => (test.to_string(), n),
+            _ => (name, 0),
+        };
+        entries.push((test, n, path));
+    }
+    entries.sort();
+
+    let mut sequences: Vec<Vec<PathBuf>> = vec![];
+    let mut last_test = None;
+    for (test, _, path) in entries {
+        if last_test.as_ref() != Some(&test) {
+            sequences.push(vec![]);
+            last_test = Some(test);
+        }
+        sequences.last_mut().unwrap().push(path);
+    }
+    Ok(sequences)
+}
+
+///
+/// Re-executes the recordings of one test, in order, against a single fresh embedded node,
+/// so each transaction sees the state the ones before it left. Returns one diff per entry.
+///
+/// The node runs without UTXO validation so the recorded coin inputs are accepted as-is.
+/// The recorded contracts are deployed again with their recorded salts and configurables,
+/// which gives them the same ids the recorded transactions refer to.
+///
+pub async fn replay(entries: &[PathBuf]) -> Result<Vec<ReplayDiff>> {
+    let config = Config {
+        utxo_validation: false,
+        ..Config::local_node()
+    };
+    let wallets = launch_custom_provider_and_get_wallets(
+        WalletsConfig::new(Some(1), Some(1), Some(1_000_000_000)),
+        Some(config),
+        None,
+    ).await;
+    let wallet = wallets[0].clone();
+    let provider = wallet.try_provider()?.clone();
+
+    let mut deployed_ids: Vec<ContractId> = vec![];
+    let mut diffs = vec![];
+    for entry in entries {
+        let recording = load(entry)?;
+        println!("▶️  replaying {} ({}) from {}", recording.intent, recording.test, entry.display());
+
+        for deployed in recording.deployments.iter() {
+            // an earlier transaction of the same test already brought it up.
+            if deployed_ids.contains(&deployed.contract_id) {
+                continue;
+            }
+            let contract_id: ContractId = Contract::load_from(&deployed.binary_path, deployed.load_configuration())?
+            .deploy(&wallet, TxParameters::default())
+            .await?
+            .into();
+            if contract_id != deployed.contract_id {
+                return Err(Error::InvalidData(format!(
+                    "{} redeployed at 0x{} but the recording used 0x{} (binary changed?)",
+                    deployed.label, contract_id, deployed.contract_id
+                )));
+            }
+            deployed_ids.push(contract_id);
+        }
+
+        let tx = FuelTransaction::from_bytes(&recording.tx_bytes)
+            .map_err(|e| Error::InvalidData(format!("recorded tx.bin does not decode: {e}")))?;
+        let tx: ScriptTransaction = match tx {
+            FuelTransaction::Script(script) => script.into(),
+            _ => return Err(Error::InvalidData("recorded tx is not a script".to_string())),
+        };
+        let receipts = match provider.send_transaction(&tx).await {
+            Ok(receipts) => receipts,
+            Err(Error::RevertTransactionError { receipts, .. }) => receipts,
+            Err(error) => return Err(error),
+        };
+        let receipts = receipt_lines(&receipts);
+
+        let target = recording
+            .deployments
+            .iter()
+            .find(|d| d.label == "TargetContract")
+            .ok_or_else(|| Error::InvalidData("recording has no TargetContract deployment".to_string()))?;
+        let target = TargetContract::new(Bech32ContractId::from(target.contract_id), wallet.clone());
+        let storage = simulate::read_storage(&wallet, &target).await?;
+
+        let mut diff = ReplayDiff::default();
+        for i in 0..recording.receipts.len().max(receipts.len()) {
+            let recorded = recording.receipts.get(i).map(String::as_str).unwrap_or("<none>");
+            let replayed = receipts.get(i).map(String::as_str).unwrap_or("<none>");
+            if recorded != replayed {
+                diff.receipts.push(format!("receipt {i}:\n\trecorded: {recorded}\n\treplayed: {replayed}"));
+            }
+        }
+        diff.storage = recording.storage.diff(&storage);
+        diffs.push(diff);
+    }
+    Ok(diffs)
+}
+
+fn storage_to_json(storage: &TargetStorage) -> Value {
+    json!({
+        "get0": storage.get0,
+        "get1": format!("0x{}", hex::encode(storage.get1.0)),
+        "get2": [storage.get2.0, storage.get2.1],
+        "get3": [storage.get3.0, storage.get3.1, storage.get3.2, storage.get3.3],
+    })
+}
+
+fn storage_from_json(value: &Value) -> Option<TargetStorage> {
+    let u = |v: &Value| v.as_u64();
+    Some(TargetStorage {
+        get0: u(&value["get0"])?,
+        get1: Bits256::from_hex_str(value["get1"].as_str()?).ok()?,
+        get2: (u(&value["get2"][0])?, u(&value["get2"][1])?),
+        get3: (
+            u(&value["get3"][0])?,
+            u(&value["get3"][1])?,
+            u(&value["get3"][2])?,
+            value["get3"][3].as_bool()?,
+        ),
+    })
+}
@@ -0,0 +1,285 @@
+use std::fs;
+use std::io;
+
+use fuels::{
+    prelude::*,
+    programs::contract::ContractCallHandler,
+    tx::Receipt,
+};
+use serde_json::{json, Value};
+
+use crate::{
+    utils::bench::{self, SetterCase},
+    utils::codegen::Section,
+    utils::context::TestContext,
+    utils::llcall::{self, LowLevelPath, FAILED_REQUIRE_SIGNAL},
+    utils::results::{self, CaseResult},
+    utils::simulate,
+    CallerScript,
+};
+
+pub const REPORT_DIR: &str = "./report";
+pub const REPORT_JSON: &str = "./report/compatibility.json";
+pub const REPORT_MD: &str = "./report/compatibility.md";
+
+const CARGO_LOCK: &str = "./Cargo.lock";
+const TOOLCHAIN_FILE: &str = "./fuel-toolchain.toml";
+
+/// The toolchain a report was produced with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Versions {
+    pub fuels: String,
+    pub forc: String,
+    pub fuel_core: String,
+}
+
+impl Versions {
+    ///
+    /// fuels from Cargo.lock, forc from fuel-toolchain.toml (build.rs refuses any other)
+    /// and fuel-core from the node itself, so a remote node reports its own version.
+    ///
+    pub async fn detect(provider: &Provider) -> Versions {
+        let fuel_core = match provider.node_info().await {
+            Ok(info) => info.node_version,
+            Err(_) => locked_version("fuel-core"),
+        };
+        Versions {
+            fuels: locked_version("fuels"),
+            forc: pinned_forc(),
+            fuel_core,
+        }
+    }
+}
+
+/// How a report row reached the setter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportPath {
+    Direct,
+    Typed,
+    LowLevel(LowLevelPath),
+    /// CallerScript calling the setter, see `bench::CallPath::Script`.
+    Script,
+    /// The setter and its getter in one SDK multicall, see `bench::CallPath::Multicall`.
+    Multicall,
+}
+
+impl ReportPath {
+    pub const ALL: [ReportPath; 6] = [
+        ReportPath::Direct,
+        ReportPath::Typed,
+        ReportPath::LowLevel(LowLevelPath::Std),
+        ReportPath::LowLevel(LowLevelPath::Asm),
+        ReportPath::Script,
+        ReportPath::Multicall,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportPath::Direct => "direct",
+            ReportPath::Typed => "typed",
+            ReportPath::LowLevel(LowLevelPath::Std) => "low-level std",
+            ReportPath::LowLevel(LowLevelPath::Asm) => "low-level asm",
+            ReportPath::Script => "script",
+            ReportPath::Multicall => "multicall",
+        }
+    }
+
+    /// `single_value_type_arg` values worth running; only low level calls take the flag.
+    pub fn flags(&self) -> Vec<Option<bool>> {
+        match self {
+            ReportPath::LowLevel(_) => vec![Some(true), Some(false)],
+            _ => vec![None],
+        }
+    }
+}
+
+/// One setter called one way, and what its getter read back afterwards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportRow {
+    pub function: String,
+    pub argument_type: String,
+    pub path: String,
+    pub single_value_type_arg: Option<bool>,
+    /// The getter read back exactly the arguments.
+    pub success: bool,
+    pub expected: String,
+    pub observed: String,
+    /// Why the transaction reverted, if it did.
+    pub failure: Option<String>,
+}
+
+///
+/// Runs `case` over `path` with `flag` on a TargetContract of its own. Failures are
+/// recorded in the row, only an SDK error before execution fails the run. The case is
+/// also saved as a `CaseResult` when LLCALL_RESULTS_DIR is set.
+///
+pub async fn run_case(case: SetterCase, path: ReportPath, flag: Option<bool>) -> Result<ReportRow> {
+    let ctx = TestContext::isolated().await;
+    let handler: ContractCallHandler<WalletUnlocked, ()> = match path {
+        ReportPath::Direct => case.direct_call(&ctx.target),
+        ReportPath::Typed => case.typed_call(&ctx.caller, ctx.target_id),
+        ReportPath::LowLevel(low_level_path) => {
+            let single_value_type_arg = flag.unwrap_or_else(|| case.single_value_type_arg());
+            let call = ctx
+                .low_level_call(case.selector(), case.calldata(), single_value_type_arg)
+                .with_path(low_level_path);
+            llcall::low_level_call_handler(&ctx.caller, &call)
+        }
+        ReportPath::Script => {
+            let script = CallerScript::new(ctx.wallet.clone(), &ctx.config.script_binary)
+                .main(ctx.target_id, case.target_call())
+                .set_contract_ids(&[Bech32ContractId::from(ctx.target_id)]);
+            let (_, receipts) = bench::receipts_of(script.call().await);
+            return finish_row(&ctx, case, path, flag, None, receipts).await;
+        }
+        ReportPath::Multicall => {
+            let (_, receipts) = case.send_multicall(&case.multicall(&ctx.wallet, &ctx.target)).await;
+            return finish_row(&ctx, case, path, flag, None, receipts).await;
+        }
+    };
+    let (tx, receipts) = results::send(&ctx.wallet, &handler).await?;
+    finish_row(&ctx, case, path, flag, Some(results::tx_id(&tx)), receipts).await
+}
+
+///
+/// The row for `case` once it ran over `path`, from what the getters read back. `tx_id` is
+/// unknown for the script and multicall paths, the SDK builds and sends those itself.
+///
+async fn finish_row(
+    ctx: &TestContext,
+    case: SetterCase,
+    path: ReportPath,
+    flag: Option<bool>,
+    tx_id: Option<String>,
+    receipts: Vec<Receipt>,
+) -> Result<ReportRow> {
+    let storage = simulate::read_storage(&ctx.wallet, &ctx.target).await?;
+
+    let row = ReportRow {
+        function: case.name().to_string(),
+        argument_type: case.argument_type().to_string(),
+        path: path.name().to_string(),
+        single_value_type_arg: flag,
+        success: case.stored_correctly(&storage),
+        expected: case.expected(),
+        observed: case.read_back(&storage),
+        failure: failure(&receipts),
+    };
+    let result = CaseResult::new(path.name(), tx_id, &receipts)
+        .with_values(row.expected.clone(), row.observed.clone(), row.success)
+        .with_receipts(ctx.trace(&receipts), &receipts);
+    results::save(&result)?;
+    Ok(row)
+}
+
+/// Every setter over every path and flag.
+pub async fn run_matrix() -> Result<Vec<ReportRow>> {
+    let mut rows = vec![];
+    for case in SetterCase::ALL {
+        for path in ReportPath::ALL {
+            for flag in path.flags() {
+                let row = run_case(case, path, flag).await?;
+                println!("{:<5} {:<14} {:<6} success={}", row.function, row.path, flag_cell(row.single_value_type_arg), row.success);
+                rows.push(row);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// The first panic or revert in `receipts`.
+pub fn failure(receipts: &[Receipt]) -> Option<String> {
+    receipts.iter().find_map(|r| match r {
+        Receipt::Panic { reason, .. } => Some(format!("panic {:?}", reason.reason())),
+        Receipt::Revert { ra, .. } if *ra == FAILED_REQUIRE_SIGNAL => Some("revert: require failed".to_string()),
+        Receipt::Revert { ra, .. } => Some(format!("revert {ra:#x}")),
+        _ => None,
+    })
+}
+
+pub fn to_markdown(versions: &Versions, rows: &[ReportRow]) -> String {
+    let mut out = format!(
+        "fuels {}, forc {}, fuel-core {}\n\n",
+        versions.fuels, versions.forc, versions.fuel_core
+    );
+    out.push_str("| function | argument type | path | single_value_type_arg | success | expected | observed | failure |\n");
+    out.push_str("|---|---|---|---|---|---|---|---|\n");
+    for r in rows {
+        out.push_str(&format!(
+            "| {} | `{}` | {} | {} | {} | `{}` | `{}` | {} |\n",
+            r.function,
+            r.argument_type,
+            r.path,
+            flag_cell(r.single_value_type_arg),
+            if r.success { "✅" } else { "❌" },
+            r.expected,
+            r.observed,
+            r.failure.as_deref().unwrap_or(""),
+        ));
+    }
+    out
+}
+
+pub fn to_json(versions: &Versions, rows: &[ReportRow]) -> Value {
+    json!({
+        "versions": {
+            "fuels": versions.fuels,
+            "forc": versions.forc,
+            "fuel_core": versions.fuel_core,
+        },
+        "rows": rows.iter().map(|r| json!({
+            "function": r.function,
+            "argument_type": r.argument_type,
+            "path": r.path,
+            "single_value_type_arg": r.single_value_type_arg,
+            "success": r.success,
+            "expected": r.expected,
+            "observed": r.observed,
+            "failure": r.failure,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+///
+/// Writes the report as JSON and Markdown under ./report, and puts the Markdown table in
+/// the README's compatibility section.
+///
+pub fn write(versions: &Versions, rows: &[ReportRow]) -> io::Result<()> {
+    let markdown = to_markdown(versions, rows);
+    fs::create_dir_all(REPORT_DIR)?;
+    fs::write(REPORT_JSON, serde_json::to_string_pretty(&to_json(versions, rows))? + "\n")?;
+    fs::write(REPORT_MD, &markdown)?;
+
+    let section = Section::markdown(crate::utils::codegen::README, "compatibility", markdown);
+    let readme = fs::read_to_string(section.path)?;
+    let updated = section.splice(&readme).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    fs::write(section.path, updated)?;
+
+    println!("Compatibility report written to {}, {} and {}", REPORT_JSON, REPORT_MD, section.path);
+    Ok(())
+}
+
+fn flag_cell(flag: Option<bool>) -> String {
+    flag.map_or_else(|| "-".to_string(), |f| f.to_string())
+}
+
+/// The version of `package` in Cargo.lock, or `unknown`.
+fn locked_version(package: &str) -> String {
+    let lock = fs::read_to_string(CARGO_LOCK).unwrap_or_default();
+    let name = format!("name = \"{package}\"");
+    let lines: Vec<&str> = lock.lines().map(str::trim).collect();
+    for pair in lines.windows(2) {
+        if let (true, Some(version)) = (pair[0] == name, pair[1].strip_prefix("version = ")) {
+            return version.trim_matches('"').to_string();
+        }
+    }
+    "unknown".to_string()
+}
+
+fn pinned_forc() -> String {
+    fs::read_to_string(TOOLCHAIN_FILE)
+        .unwrap_or_default()
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("forc = ").map(|v| v.trim_matches('"').to_string()))
+        .unwrap_or_else(|| "unknown".to_string())
+}
@@ -0,0 +1,215 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use fuels::{
+    prelude::*,
+    programs::contract::ContractCallHandler,
+    tx::Receipt,
+    types::transaction::{ScriptTransaction, Transaction},
+};
+use serde_json::{json, Value};
+
+use crate::utils::{bench, record, report};
+
+/// When set, every case the harness runs leaves a JSON result under this directory.
+pub const RESULTS_DIR_ENV: &str = "LLCALL_RESULTS_DIR";
+/// Written next to the results by the `export_junit` test.
+pub const JUNIT_FILE: &str = "junit.xml";
+
+pub fn results_dir() -> Option<PathBuf> {
+    std::env::var(RESULTS_DIR_ENV).ok().map(PathBuf::from)
+}
+
+/// The outcome of one call a test made, for CI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaseResult {
+    pub test: String,
+    /// How the call reached TargetContract: `direct`, `low-level std`, `script`, ...
+    pub path: String,
+    /// Only known when the harness built the transaction itself.
+    pub tx_id: Option<String>,
+    pub gas_used: u64,
+    pub success: bool,
+    pub expected: Option<String>,
+    pub observed: Option<String>,
+    /// Why it failed, e.g. `panic MemoryOverflow`.
+    pub failure: Option<String>,
+    /// The call tree and the raw receipts, only kept for failed cases.
+    pub receipts: Vec<String>,
+}
+
+impl CaseResult {
+    /// A result for the running test, successful unless `receipts` show a panic or revert.
+    pub fn new(path: &str, tx_id: Option<String>, receipts: &[Receipt]) -> CaseResult {
+        let failure = report::failure(receipts);
+        CaseResult {
+            test: record::current_test_name(),
+            path: path.to_string(),
+            tx_id,
+            gas_used: bench::gas_used(receipts),
+            success: failure.is_none(),
+            expected: None,
+            observed: None,
+            failure,
+            receipts: vec![],
+        }
+    }
+
+    /// Compares the values too: the case only succeeds when they match.
+    pub fn with_values(mut self, expected: String, observed: String, matched: bool) -> CaseResult {
+        self.success &= matched;
+        self.expected = Some(expected);
+        self.observed = Some(observed);
+        self
+    }
+
+    ///
+    /// Attaches the decoded call tree and the raw receipts, if the case failed. Passing
+    /// cases stay small.
+    ///
+    pub fn with_receipts(mut self, trace: impl ToString, receipts: &[Receipt]) -> CaseResult {
+        if !self.success {
+            self.receipts = trace.to_string().lines().map(str::to_string).collect();
+            self.receipts.extend(record::receipt_lines(receipts));
+        }
+        self
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "test": self.test,
+            "path": self.path,
+            "tx_id": self.tx_id,
+            "gas_used": self.gas_used,
+            "success": self.success,
+            "expected": self.expected,
+            "observed": self.observed,
+            "failure": self.failure,
+            "receipts": self.receipts,
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Option<CaseResult> {
+        let text = |key: &str| value[key].as_str().map(str::to_string);
+        Some(CaseResult {
+            test: text("test")?,
+            path: text("path")?,
+            tx_id: text("tx_id"),
+            gas_used: value["gas_used"].as_u64()?,
+            success: value["success"].as_bool()?,
+            expected: text("expected"),
+            observed: text("observed"),
+            failure: text("failure"),
+            receipts: value["receipts"]
+                .as_array()?
+                .iter()
+                .filter_map(|r| r.as_str().map(str::to_string))
+                .collect(),
+        })
+    }
+}
+
+///
+/// Sends `handler` as a transaction built here, so its id is known, and returns it with
+/// its receipts, whether it succeeded or reverted.
+///
+pub async fn send(
+    wallet: &WalletUnlocked,
+    handler: &ContractCallHandler<WalletUnlocked, ()>,
+) -> Result<(ScriptTransaction, Vec<Receipt>)> {
+    let tx = handler.build_tx().await?;
+    match wallet.try_provider()?.send_transaction(&tx).await {
+        Ok(receipts) => Ok((tx, receipts)),
+        Err(Error::RevertTransactionError { receipts, .. }) => Ok((tx, receipts)),
+        Err(error) => Err(error),
+    }
+}
+
+pub fn tx_id(tx: &ScriptTransaction) -> String {
+    format!("0x{}", tx.id())
+}
+
+/// Writes `result` to `<dir>/<test>-<n>.json` when LLCALL_RESULTS_DIR is set.
+pub fn save(result: &CaseResult) -> io::Result<()> {
+    let Some(dir) = results_dir() else {
+        return Ok(());
+    };
+    if result.test.is_empty() {
+        return Err(record::unnamed_test());
+    }
+    fs::create_dir_all(&dir)?;
+    let mut n = 0;
+    let path = loop {
+        let candidate = dir.join(format!("{}-{}.json", result.test, n));
+        if !candidate.exists() {
+            break candidate;
+        }
+        n += 1;
+    };
+    fs::write(path, serde_json::to_string_pretty(&result.to_json())?)
+}
+
+/// Every result under `dir`, sorted by test.
+pub fn load_all(dir: &Path) -> io::Result<Vec<CaseResult>> {
+    let mut results = vec![];
+    for entry in fs::read_dir(dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().map_or(true, |e| e != "json") {
+            continue;
+        }
+        let value: Value = serde_json::from_str(&fs::read_to_string(&path)?)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        if let Some(result) = CaseResult::from_json(&value) {
+            results.push(result);
+        }
+    }
+    results.sort_by(|a, b| (&a.test, &a.path).cmp(&(&b.test, &b.path)));
+    Ok(results)
+}
+
+///
+/// The results as a JUnit XML test suite, one test case per result. Failures carry the
+/// decoded receipts, every case carries its tx id, gas and values in system-out.
+///
+pub fn to_junit(results: &[CaseResult]) -> String {
+    let failures = results.iter().filter(|r| !r.success).count();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuite name=\"llcall_testing\" tests=\"{}\" failures=\"{failures}\">\n",
+        results.len()
+    ));
+    for r in results {
+        out.push_str(&format!(
+            "  <testcase classname=\"integration_tests\" name=\"{}\">\n",
+            xml_escape(&format!("{} [{}]", r.test, r.path))
+        ));
+        if !r.success {
+            let message = r.failure.clone().unwrap_or_else(|| "observed value differs from expected".to_string());
+            out.push_str(&format!(
+                "    <failure message=\"{}\">{}</failure>\n",
+                xml_escape(&message),
+                xml_escape(&r.receipts.join("\n"))
+            ));
+        }
+        let mut details = vec![
+            format!("tx id: {}", r.tx_id.as_deref().unwrap_or("unknown")),
+            format!("gas used: {}", r.gas_used),
+        ];
+        if let (Some(expected), Some(observed)) = (&r.expected, &r.observed) {
+            details.push(format!("expected: {expected}"));
+            details.push(format!("observed: {observed}"));
+        }
+        out.push_str(&format!("    <system-out>{}</system-out>\n", xml_escape(&details.join("\n"))));
+        out.push_str("  </testcase>\n");
+    }
+    out.push_str("</testsuite>\n");
+    out
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
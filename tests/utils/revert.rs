@@ -0,0 +1,70 @@
+use std::fmt;
+
+use fuels::{
+    prelude::*,
+    tx::Receipt,
+};
+
+use crate::{
+    utils::llcall::{self, FAILED_REQUIRE_SIGNAL, UNKNOWN_SELECTOR_REVERT_CODE},
+    utils::selectors::{self, SelectorRegistry},
+    TargetContract, TargetError,
+};
+
+/// Why TargetContract reverted, recovered from the receipts of the whole transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetRevert {
+    /// A `require` that logged a `TargetError`.
+    Error(TargetError),
+    /// A `require` that logged anything else, as decoded by the SDK.
+    Message(String),
+    /// The fallback's revert, with the selector it logged and what the registry knows it as.
+    UnknownSelector { selector: u64, known_as: String },
+    /// A revert that logged nothing we can decode.
+    Code(u64),
+}
+
+impl fmt::Display for TargetRevert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TargetRevert::Error(error) => write!(f, "TargetContract reverted with {error:?}"),
+            TargetRevert::Message(message) => write!(f, "TargetContract reverted: {message}"),
+            TargetRevert::UnknownSelector { known_as, .. } => {
+                write!(f, "TargetContract has no {known_as}, its fallback reverted")
+            }
+            TargetRevert::Code(code) => write!(f, "TargetContract reverted with code {code:#x}"),
+        }
+    }
+}
+
+impl std::error::Error for TargetRevert {}
+
+///
+/// The revert TargetContract caused, if any. Works the same whether the target was called
+/// directly or through CallerContract: the revert receipt and the target's logs end up in
+/// the receipts of the transaction either way.
+///
+pub fn target_revert(target: &TargetContract<WalletUnlocked>, receipts: &[Receipt]) -> Option<TargetRevert> {
+    let target_id = ContractId::from(target.contract_id());
+    let code = llcall::revert_code(receipts, target_id)?;
+    if code == UNKNOWN_SELECTOR_REVERT_CODE {
+        if let Some(&selector) = llcall::logged_words(receipts, target_id).last() {
+            let registry = SelectorRegistry::scan(selectors::CONTRACTS_DIR).unwrap_or_default();
+            return Some(TargetRevert::UnknownSelector { selector, known_as: registry.describe(selector) });
+        }
+    }
+    if code != FAILED_REQUIRE_SIGNAL {
+        return Some(TargetRevert::Code(code));
+    }
+
+    let decoder = target.log_decoder();
+    if let Some(error) = decoder
+        .decode_logs_with_type::<TargetError>(receipts)
+        .ok()
+        .and_then(|errors| errors.last().cloned())
+    {
+        return Some(TargetRevert::Error(error));
+    }
+    let message = decoder.decode_logs(receipts).filter_succeeded().last().cloned();
+    Some(message.map(TargetRevert::Message).unwrap_or(TargetRevert::Code(code)))
+}
@@ -0,0 +1,123 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::utils::abi::{self, AbiFunction};
+
+/// Where the Sway projects live; each has its ABI JSONs under out/<profile>.
+pub const CONTRACTS_DIR: &str = "./contracts";
+/// Selector the `resolve_selector` test looks up, as a hex word (`0x00000000a1b2c3d4`).
+pub const SELECTOR_ENV: &str = "LLCALL_SELECTOR";
+
+/// One function of one ABI, keyed by its selector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectorEntry {
+    pub selector: u64,
+    /// The project the ABI belongs to, e.g. `target`.
+    pub contract: String,
+    pub function: AbiFunction,
+}
+
+impl SelectorEntry {
+    pub fn signature(&self) -> String {
+        self.function.signature()
+    }
+}
+
+/// Two different signatures that hash to the same selector.
+#[derive(Debug, Clone)]
+pub struct Collision {
+    pub selector: u64,
+    pub entries: Vec<SelectorEntry>,
+}
+
+/// Every function selector of every ABI JSON found under `contracts/*/out`.
+#[derive(Debug, Clone, Default)]
+pub struct SelectorRegistry {
+    pub entries: Vec<SelectorEntry>,
+}
+
+impl SelectorRegistry {
+    pub fn scan(contracts_dir: &str) -> io::Result<SelectorRegistry> {
+        let mut abi_files = vec![];
+        for project in fs::read_dir(contracts_dir)?.flatten() {
+            let Ok(profiles) = fs::read_dir(project.path().join("out")) else {
+                continue;
+            };
+            for profile in profiles.flatten() {
+                for file in fs::read_dir(profile.path())?.flatten() {
+                    let path = file.path();
+                    if path.to_string_lossy().ends_with("-abi.json") {
+                        abi_files.push(path);
+                    }
+                }
+            }
+        }
+        abi_files.sort();
+
+        let mut registry = SelectorRegistry::default();
+        for path in abi_files {
+            let contract = contract_name(&path);
+            for function in abi::load_abi(&path.to_string_lossy())?.functions {
+                let entry = SelectorEntry {
+                    selector: function.selector_word(),
+                    contract: contract.clone(),
+                    function,
+                };
+                // the same ABI built for debug and release is listed once.
+                if !registry.entries.contains(&entry) {
+                    registry.entries.push(entry);
+                }
+            }
+        }
+        Ok(registry)
+    }
+
+    pub fn resolve(&self, selector: u64) -> Vec<&SelectorEntry> {
+        self.entries.iter().filter(|e| e.selector == selector).collect()
+    }
+
+    ///
+    /// `set2(u64,u64)` for a known selector, with the contracts declaring it when there are
+    /// several, or the selector in hex when no ABI has it.
+    ///
+    pub fn describe(&self, selector: u64) -> String {
+        let mut signatures: Vec<String> = self.resolve(selector).iter().map(|e| e.signature()).collect();
+        signatures.dedup();
+        match signatures.as_slice() {
+            [] => format!("{selector:#018x}"),
+            [signature] => signature.clone(),
+            _ => format!("{selector:#018x} (ambiguous: {})", signatures.join(" | ")),
+        }
+    }
+
+    /// Selectors shared by functions with different signatures.
+    pub fn collisions(&self) -> Vec<Collision> {
+        let mut selectors: Vec<u64> = self.entries.iter().map(|e| e.selector).collect();
+        selectors.sort();
+        selectors.dedup();
+        selectors
+            .into_iter()
+            .filter_map(|selector| {
+                let entries: Vec<SelectorEntry> = self.resolve(selector).into_iter().cloned().collect();
+                let first = entries[0].signature();
+                entries
+                    .iter()
+                    .any(|e| e.signature() != first)
+                    .then_some(Collision { selector, entries })
+            })
+            .collect()
+    }
+}
+
+/// `0x00000000a1b2c3d4` or `a1b2c3d4` as the selector word.
+pub fn parse_selector(text: &str) -> Option<u64> {
+    u64::from_str_radix(text.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// `contracts/targetcontract/out/debug/target-abi.json` -> `target`.
+fn contract_name(path: &Path) -> String {
+    path.file_name()
+        .map(|f| f.to_string_lossy().trim_end_matches("-abi.json").to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,205 @@
+use fuels::{
+    prelude::*,
+    programs::contract::{ContractCallHandler, MultiContractCallHandler},
+    tx::Receipt,
+    types::{transaction_builders::CreateTransactionBuilder, Bits256},
+};
+
+use crate::{
+    utils::bench::gas_used,
+    utils::report,
+    utils::revert,
+    TargetContract,
+};
+
+/// Set to `1` or `true` to dry-run deploys and low-level calls instead of committing them.
+pub const SIMULATE_ENV: &str = "LLCALL_SIMULATE";
+
+pub fn simulation_enabled() -> bool {
+    matches!(
+        std::env::var(SIMULATE_ENV).as_deref(),
+        Ok("1") | Ok("true")
+    )
+}
+
+/// Everything TargetContract's getters can see.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TargetStorage {
+    pub get0: u64,
+    pub get1: Bits256,
+    pub get2: (u64, u64),
+    pub get3: (u64, u64, u64, bool),
+}
+
+type StorageTuple = (u64, Bits256, (u64, u64), (u64, u64, u64, bool));
+
+impl From<StorageTuple> for TargetStorage {
+    fn from((get0, get1, get2, get3): StorageTuple) -> Self {
+        TargetStorage { get0, get1, get2, get3 }
+    }
+}
+
+impl TargetStorage {
+    /// One line per getter whose value differs between `self` and `after`.
+    pub fn diff(&self, after: &TargetStorage) -> Vec<String> {
+        let mut changes = vec![];
+        if self.get0 != after.get0 {
+            changes.push(format!("get0: {:?} -> {:?}", self.get0, after.get0));
+        }
+        if self.get1 != after.get1 {
+            changes.push(format!("get1: 0x{} -> 0x{}", hex::encode(self.get1.0), hex::encode(after.get1.0)));
+        }
+        if self.get2 != after.get2 {
+            changes.push(format!("get2: {:?} -> {:?}", self.get2, after.get2));
+        }
+        if self.get3 != after.get3 {
+            changes.push(format!("get3: {:?} -> {:?}", self.get3, after.get3));
+        }
+        changes
+    }
+}
+
+/// Result of a call that was dry-run and not committed.
+#[derive(Debug)]
+pub struct Simulation {
+    pub receipts: Vec<Receipt>,
+    pub logs: Vec<String>,
+    pub gas_used: u64,
+    /// Why the call would revert or panic, decoded as far as TargetContract's errors allow.
+    pub revert: Option<String>,
+    pub storage_before: TargetStorage,
+    /// The same as `storage_before` when the call would revert.
+    pub storage_after: TargetStorage,
+}
+
+impl Simulation {
+    pub fn print(&self) {
+        println!("🧪 simulated, gas used = {}, {} receipts", self.gas_used, self.receipts.len());
+        if let Some(revert) = &self.revert {
+            println!("\t❌ would revert: {revert}");
+        }
+        for log in self.logs.iter() {
+            println!("\tlog: {log}");
+        }
+        let changes = self.storage_before.diff(&self.storage_after);
+        if changes.is_empty() {
+            println!("\tno storage changes");
+        }
+        for change in changes.iter() {
+            println!("\tstorage {change}");
+        }
+    }
+}
+
+/// Result of a contract deployment that was dry-run and not committed.
+#[derive(Debug)]
+pub struct DeploySimulation {
+    pub contract_id: ContractId,
+    pub receipts: Vec<Receipt>,
+}
+
+///
+/// Reads every TargetContract getter in one dry-run multicall.
+///
+pub async fn read_storage(wallet: &WalletUnlocked, target: &TargetContract<WalletUnlocked>) -> Result<TargetStorage> {
+    let mut multi = MultiContractCallHandler::new(wallet.clone());
+    multi
+        .add_call(target.methods().get0())
+        .add_call(target.methods().get1())
+        .add_call(target.methods().get2())
+        .add_call(target.methods().get3());
+    let response = multi.simulate::<StorageTuple>().await?;
+    Ok(response.value.into())
+}
+
+///
+/// Dry-runs `handler` followed by every TargetContract getter in the same transaction,
+/// so the getters see the writes the call would make. A call that would revert is still
+/// a `Simulation`, with its receipts, logs and `revert`; only failing to dry-run is an error.
+///
+pub async fn simulate_call(
+    wallet: &WalletUnlocked,
+    target: &TargetContract<WalletUnlocked>,
+    handler: ContractCallHandler<WalletUnlocked, ()>,
+) -> Result<Simulation> {
+    let storage_before = read_storage(wallet, target).await?;
+
+    let mut multi = MultiContractCallHandler::new(wallet.clone());
+    multi
+        .add_call(handler)
+        .add_call(target.methods().get0())
+        .add_call(target.methods().get1())
+        .add_call(target.methods().get2())
+        .add_call(target.methods().get3());
+    let log_decoder = multi.log_decoder.clone();
+
+    let response = multi
+        .simulate::<((), u64, Bits256, (u64, u64), (u64, u64, u64, bool))>()
+        .await;
+    let (receipts, storage_after) = match response {
+        Ok(response) => {
+            let ((), get0, get1, get2, get3) = response.value;
+            (response.receipts, (get0, get1, get2, get3).into())
+        }
+        // nothing the call wrote would be kept.
+        Err(Error::RevertTransactionError { receipts, .. }) => (receipts, storage_before.clone()),
+        Err(error) => return Err(error),
+    };
+
+    Ok(Simulation {
+        logs: log_decoder.decode_logs(&receipts).filter_succeeded(),
+        gas_used: gas_used(&receipts),
+        revert: revert::target_revert(target, &receipts)
+            .map(|r| r.to_string())
+            .or_else(|| report::failure(&receipts)),
+        receipts,
+        storage_before,
+        storage_after,
+    })
+}
+
+///
+/// Sends `handler`, or dry-runs it under LLCALL_SIMULATE, and returns its receipts, whether
+/// it succeeded or reverted, with what the TargetContract getters see afterwards.
+///
+pub async fn call_and_read(
+    wallet: &WalletUnlocked,
+    target: &TargetContract<WalletUnlocked>,
+    handler: ContractCallHandler<WalletUnlocked, ()>,
+) -> Result<(Vec<Receipt>, TargetStorage)> {
+    if simulation_enabled() {
+        let sim = simulate_call(wallet, target, handler).await?;
+        sim.print();
+        return Ok((sim.receipts, sim.storage_after));
+    }
+    let receipts = match handler.call().await {
+        Ok(response) => response.receipts,
+        Err(Error::RevertTransactionError { receipts, .. }) => receipts,
+        Err(error) => return Err(error),
+    };
+    Ok((receipts, read_storage(wallet, target).await?))
+}
+
+///
+/// Builds and funds the create transaction for `binary_path` and dry-runs it.
+/// The returned contract id is the one a real deploy with the same salt would get.
+///
+pub async fn simulate_deploy(wallet: &WalletUnlocked, binary_path: &str, salt: [u8; 32]) -> Result<DeploySimulation> {
+    let contract = Contract::load_from(binary_path, LoadConfiguration::default().set_salt(salt))?;
+
+    let tb = CreateTransactionBuilder::prepare_contract_deployment(
+        contract.binary().to_vec(),
+        contract.contract_id(),
+        contract.state_root(),
+        contract.salt(),
+        contract.storage_slots().to_vec(),
+        TxParameters::default(),
+    );
+    let tx = wallet.add_fee_resources(tb, 0, Some(1)).await?;
+    let receipts = wallet.try_provider()?.dry_run(&tx).await?;
+
+    Ok(DeploySimulation {
+        contract_id: contract.contract_id(),
+        receipts,
+    })
+}
@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use fuels::{
+    core::codec::{calldata, fn_selector},
+    prelude::*,
+    types::errors::Error,
+};
+use tokio::task::JoinSet;
+
+use crate::{
+    utils::context::TestContext,
+    utils::llcall::{self, LowLevelCall, LowLevelPath},
+    utils::report,
+    utils::simulate::{self, TargetStorage},
+    CallerContract,
+};
+
+/// Number of signers `stress_low_level_calls` spawns.
+pub const STRESS_SIGNERS_ENV: &str = "LLCALL_STRESS_SIGNERS";
+/// Number of calls each of them sends.
+pub const STRESS_CALLS_ENV: &str = "LLCALL_STRESS_CALLS";
+
+pub const DEFAULT_STRESS_SIGNERS: usize = 8;
+pub const DEFAULT_STRESS_CALLS: usize = 4;
+
+/// Times a call is resubmitted after losing a coin to another transaction of its signer.
+pub const MAX_RETRIES: usize = 5;
+const RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StressConfig {
+    pub signers: usize,
+    pub calls_per_signer: usize,
+}
+
+impl StressConfig {
+    pub fn from_env() -> StressConfig {
+        let read = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .map(|v| v.parse().unwrap_or_else(|_| panic!("❌ {name} is not a number: {v}")))
+                .unwrap_or(default)
+        };
+        StressConfig {
+            signers: read(STRESS_SIGNERS_ENV, DEFAULT_STRESS_SIGNERS),
+            calls_per_signer: read(STRESS_CALLS_ENV, DEFAULT_STRESS_CALLS),
+        }
+    }
+}
+
+/// One low level write of the run. Values are unique across the run and never zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StressWrite {
+    Set0(u64),
+    /// Two words written by one call: seeing them from different calls means a torn write.
+    Set2(u64, u64),
+}
+
+impl StressWrite {
+    /// The `n`th write of signer `signer`: set0 and set2 in turn.
+    pub fn nth(signer: usize, n: usize) -> StressWrite {
+        let (signer, n) = (signer as u64 + 1, n as u64 + 1);
+        if n % 2 == 1 {
+            StressWrite::Set0((signer << 32) | n)
+        } else {
+            StressWrite::Set2(signer, n)
+        }
+    }
+
+    pub fn low_level_call(&self, target: ContractId) -> LowLevelCall {
+        let (function_selector, calldata, single_value_type_arg) = match *self {
+            StressWrite::Set0(value) => (fn_selector!(set0(u64)), calldata!(value), true),
+            StressWrite::Set2(a, b) => (fn_selector!(set2(u64, u64)), calldata!(a, b), false),
+        };
+        LowLevelCall {
+            target,
+            function_selector,
+            calldata,
+            single_value_type_arg,
+            path: LowLevelPath::Std,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallStatus {
+    Committed,
+    /// Executed and reverted, with the reason.
+    Reverted(String),
+    /// Never executed: the last error, after any retries.
+    GaveUp(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StressCall {
+    pub signer: usize,
+    /// The signer's `n`th call, see `StressWrite::nth`.
+    pub n: usize,
+    pub write: StressWrite,
+    pub status: CallStatus,
+    /// Submissions, 1 when the first one went through.
+    pub attempts: usize,
+}
+
+/// Everything a stress run sent, and what TargetContract held afterwards.
+#[derive(Debug, Clone)]
+pub struct StressReport {
+    pub config: StressConfig,
+    pub calls: Vec<StressCall>,
+    pub elapsed: Duration,
+    pub storage: TargetStorage,
+}
+
+impl StressReport {
+    fn count(&self, status: impl Fn(&CallStatus) -> bool) -> usize {
+        self.calls.iter().filter(|c| status(&c.status)).count()
+    }
+
+    pub fn committed(&self) -> usize {
+        self.count(|s| *s == CallStatus::Committed)
+    }
+
+    pub fn reverted(&self) -> usize {
+        self.count(|s| matches!(s, CallStatus::Reverted(_)))
+    }
+
+    pub fn gave_up(&self) -> usize {
+        self.count(|s| matches!(s, CallStatus::GaveUp(_)))
+    }
+
+    pub fn retries(&self) -> usize {
+        self.calls.iter().map(|c| c.attempts - 1).sum()
+    }
+
+    /// Committed calls per second, from the first submission to the last response.
+    pub fn throughput(&self) -> f64 {
+        self.committed() as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+
+    pub fn violations(&self) -> Vec<String> {
+        serialisation_violations(&self.calls, &self.storage)
+    }
+
+    pub fn print(&self) {
+        println!(
+            "{} signers x {} calls in {:.2?}: {} committed, {} reverted, {} gave up, {} retries, {:.1} tx/s",
+            self.config.signers,
+            self.config.calls_per_signer,
+            self.elapsed,
+            self.committed(),
+            self.reverted(),
+            self.gave_up(),
+            self.retries(),
+            self.throughput(),
+        );
+        for call in self.calls.iter().filter(|c| c.status != CallStatus::Committed) {
+            println!("❌ signer {} call {} {:?}: {:?} after {} attempt(s)", call.signer, call.n, call.write, call.status, call.attempts);
+        }
+        println!("get0() = {:#x}, get2() = {:?}", self.storage.get0, self.storage.get2);
+    }
+}
+
+///
+/// Whether some order of the committed writes leaves `storage` as it is: each getter must
+/// show the last write to it, so its value has to come from a committed write, both words
+/// of get2 from the same one. A fresh TargetContract shows zeros until it is written.
+///
+pub fn serialisation_violations(calls: &[StressCall], storage: &TargetStorage) -> Vec<String> {
+    let committed: HashSet<StressWrite> = calls
+        .iter()
+        .filter(|c| c.status == CallStatus::Committed)
+        .map(|c| c.write)
+        .collect();
+    let set0: HashSet<u64> = committed.iter().filter_map(|w| match w {
+        StressWrite::Set0(value) => Some(*value),
+        _ => None,
+    }).collect();
+    let set2: HashSet<(u64, u64)> = committed.iter().filter_map(|w| match w {
+        StressWrite::Set2(a, b) => Some((*a, *b)),
+        _ => None,
+    }).collect();
+
+    let mut found = vec![];
+    let get0_valid = if set0.is_empty() { storage.get0 == 0 } else { set0.contains(&storage.get0) };
+    if !get0_valid {
+        found.push(format!("get0() = {:#x} was not written by any committed set0", storage.get0));
+    }
+    let (a, b) = storage.get2;
+    let get2_valid = if set2.is_empty() { (a, b) == (0, 0) } else { set2.contains(&(a, b)) };
+    if !get2_valid {
+        let torn = set2.iter().any(|w| w.0 == a) && set2.iter().any(|w| w.1 == b);
+        let why = if torn { "a torn write, its words come from different set2 calls" } else { "not written by any committed set2" };
+        found.push(format!("get2() = ({a}, {b}) is {why}"));
+    }
+    found
+}
+
+///
+/// A coin picked by one transaction of a signer was already spent, or is being spent, by
+/// another one of its transactions. fuel-core's txpool rejects the later transaction, and
+/// resubmitting it picks the change coin once the first one is in a block.
+///
+pub fn is_contention(error: &Error) -> bool {
+    let message = error.to_string().to_lowercase();
+    ["utxo", "already spent", "not inserted", "collided"]
+        .iter()
+        .any(|needle| message.contains(needle))
+}
+
+///
+/// Gets `config.signers` signers (see `wallets::signers`), then sends all their writes at once through
+/// CallerContract, each signer's calls competing for its coins. Reads TargetContract back
+/// once every call has committed, reverted or run out of retries.
+///
+pub async fn run(ctx: &TestContext, config: StressConfig) -> StressReport {
+    let signers = ctx.signers(config.signers).await;
+
+    let start = Instant::now();
+    let mut tasks = JoinSet::new();
+    // interleave the signers: every signer's first call, then every second call, ...
+    for n in 0..config.calls_per_signer {
+        for (i, signer) in signers.iter().enumerate() {
+            let caller = CallerContract::new(Bech32ContractId::from(ctx.caller_id), signer.clone());
+            let write = StressWrite::nth(i, n);
+            let call = write.low_level_call(ctx.target_id);
+            tasks.spawn(async move {
+                let (status, attempts) = send_with_retries(&caller, &call).await;
+                StressCall { signer: i, n, write, status, attempts }
+            });
+        }
+    }
+    let mut calls = vec![];
+    while let Some(call) = tasks.join_next().await {
+        calls.push(call.unwrap());
+    }
+    let elapsed = start.elapsed();
+    calls.sort_by_key(|c| (c.signer, c.n));
+
+    let storage = simulate::read_storage(&ctx.wallet, &ctx.target).await.unwrap();
+    StressReport { config, calls, elapsed, storage }
+}
+
+async fn send_with_retries(caller: &CallerContract<WalletUnlocked>, call: &LowLevelCall) -> (CallStatus, usize) {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match llcall::low_level_call_handler(caller, call).call().await {
+            Ok(_) => return (CallStatus::Committed, attempts),
+            Err(Error::RevertTransactionError { receipts, .. }) => {
+                let reason = report::failure(&receipts).unwrap_or_else(|| "reverted".to_string());
+                return (CallStatus::Reverted(reason), attempts);
+            }
+            Err(error) if is_contention(&error) && attempts <= MAX_RETRIES => {
+                tokio::time::sleep(RETRY_BACKOFF * attempts as u32).await;
+            }
+            Err(error) => return (CallStatus::GaveUp(error.to_string()), attempts),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use std::fmt;
+
+use fuels::{
+    prelude::*,
+    tx::{field::ScriptData, ConsensusParameters, PanicReason, Receipt, Script},
+    types::transaction::ScriptTransaction,
+};
+
+use crate::utils::{
+    abi::{self, Abi, AbiFunction, AbiType},
+    encoding::{self, AbiValue, Encoding},
+    manifest::DeployedContract,
+    selectors::SelectorRegistry,
+};
+
+/// A contract the trace can name, with the ABI its selectors are resolved against.
+#[derive(Debug, Clone)]
+pub struct TraceContract {
+    pub contract_id: ContractId,
+    pub label: String,
+    pub abi: Abi,
+}
+
+impl TraceContract {
+    /// Labels the contract as in the manifest and loads the ABI next to its binary.
+    pub fn from_deployment(deployed: &DeployedContract) -> TraceContract {
+        TraceContract {
+            contract_id: deployed.contract_id,
+            label: deployed.label.clone(),
+            abi: abi::load_abi(&abi::abi_path_for_binary(&deployed.binary_path)).unwrap_or_default(),
+        }
+    }
+}
+
+///
+/// A transaction's script data and the address the VM loads it at. The SDK writes the
+/// arguments of the calls it makes there, heap data included, so their pointers lead into it.
+///
+#[derive(Debug, Clone, Default)]
+pub struct ScriptMemory {
+    pub address: u64,
+    pub bytes: Vec<u8>,
+}
+
+impl ScriptMemory {
+    pub fn of(tx: &ScriptTransaction, params: &ConsensusParameters) -> ScriptMemory {
+        let script: Script = tx.clone().into();
+        ScriptMemory {
+            address: (params.tx_offset() + script.script_data_offset()) as u64,
+            bytes: script.script_data().clone(),
+        }
+    }
+
+    /// `of`, with the consensus parameters of `wallet`'s node.
+    pub async fn load(wallet: &WalletUnlocked, tx: &ScriptTransaction) -> Result<ScriptMemory> {
+        let params = wallet.try_provider()?.chain_info().await?.consensus_parameters;
+        Ok(ScriptMemory::of(tx, &params))
+    }
+
+    /// The bytes from `ptr` to the end of the script data, if `ptr` points into it.
+    fn from(&self, ptr: u64) -> Option<&[u8]> {
+        let start = ptr.checked_sub(self.address)?;
+        self.bytes.get(usize::try_from(start).ok()?..)
+    }
+
+    fn read(&self, ptr: u64, len: u64) -> Option<&[u8]> {
+        self.from(ptr)?.get(..usize::try_from(len).ok()?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// Return receipt, with its value.
+    Returned(u64),
+    /// ReturnData receipt, with the returned bytes.
+    ReturnedData(Vec<u8>),
+    /// This frame reverted with the given code.
+    Reverted(u64),
+    /// This frame panicked.
+    Panicked(String),
+    /// The transaction ended while this frame was still running, because something it
+    /// called reverted or panicked.
+    Aborted,
+}
+
+/// One call frame: the script at the root, then every contract call nested under its caller.
+#[derive(Debug, Clone)]
+pub struct CallNode {
+    pub contract_id: ContractId,
+    pub label: String,
+    /// The resolved signature, or the selector in hex when no known ABI has it.
+    pub function: String,
+    pub arguments: String,
+    pub gas_forwarded: u64,
+    ///
+    /// The script's total for the root. A frame that ran out of gas used all it was
+    /// forwarded. Otherwise unknown: only Call receipts carry gas, the Return, ReturnData
+    /// and Revert receipts closing a frame don't say how much it had left.
+    ///
+    pub gas_used: Option<u64>,
+    pub logs: Vec<String>,
+    pub outcome: Outcome,
+    pub children: Vec<CallNode>,
+    /// The `Bytes` arguments of this frame, the calldata a low-level call passes on.
+    payloads: Vec<Vec<u8>>,
+}
+
+impl CallNode {
+    fn new(contract_id: ContractId, label: String, function: String, arguments: String, gas_forwarded: u64) -> CallNode {
+        CallNode {
+            contract_id,
+            label,
+            function,
+            arguments,
+            gas_forwarded,
+            gas_used: None,
+            logs: vec![],
+            outcome: Outcome::Aborted,
+            children: vec![],
+            payloads: vec![],
+        }
+    }
+
+    /// Every node of the tree, depth first, with its depth (the script is 0).
+    pub fn walk(&self) -> Vec<(usize, &CallNode)> {
+        let mut nodes = vec![];
+        self.walk_into(0, &mut nodes);
+        nodes
+    }
+
+    fn walk_into<'a>(&'a self, depth: usize, nodes: &mut Vec<(usize, &'a CallNode)>) {
+        nodes.push((depth, self));
+        for child in self.children.iter() {
+            child.walk_into(depth + 1, nodes);
+        }
+    }
+}
+
+impl fmt::Display for CallNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (depth, node) in self.walk() {
+            let indent = "    ".repeat(depth);
+            let outcome = match &node.outcome {
+                Outcome::Returned(value) => format!("✅ returned {value}"),
+                Outcome::ReturnedData(data) => format!("✅ returned 0x{}", hex::encode(data)),
+                Outcome::Reverted(code) => format!("❌ reverted {code:#x}"),
+                Outcome::Panicked(reason) => format!("❌ panicked {reason}"),
+                Outcome::Aborted => "❌ aborted".to_string(),
+            };
+            let gas = match (node.gas_used, node.gas_forwarded) {
+                (Some(used), 0) => format!("gas used {used}"),
+                (Some(used), forwarded) => format!("gas used {used} of {forwarded}"),
+                (None, forwarded) => format!("gas {forwarded}"),
+            };
+            writeln!(f, "{indent}{} {}{} [{gas}] {outcome}", node.label, node.function, node.arguments)?;
+            for log in node.logs.iter() {
+                writeln!(f, "{indent}    📝 {log}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// Rebuilds the call tree of a transaction from its receipts.
+///
+/// Each Call receipt opens a frame under the innermost open one, and the frame's Return,
+/// ReturnData, Revert or Panic receipt closes it. Logs go to the frame that emitted them.
+/// The call's selector is resolved against the ABI of the called contract, or against every
+/// ABI in `registry` when the contract isn't known.
+///
+/// Arguments passed by value are decoded from param2. Arguments behind a pointer are
+/// decoded from `memory` when it points there, which is the case for the calls the SDK
+/// makes, or else from the calldata the calling frame was given, which is what a low-level
+/// call passes on. Any other pointer is shown as is.
+///
+pub fn build(
+    receipts: &[Receipt],
+    memory: Option<&ScriptMemory>,
+    contracts: &[TraceContract],
+    registry: &SelectorRegistry,
+) -> CallNode {
+    let known = |id: &ContractId| contracts.iter().find(|c| c.contract_id == *id);
+
+    let mut stack = vec![CallNode::new(ContractId::zeroed(), "Script".to_string(), String::new(), String::new(), 0)];
+    for receipt in receipts {
+        match receipt {
+            Receipt::Call { to, gas, param1, param2, .. } => {
+                let contract = known(to);
+                let label = contract.map_or_else(|| format!("0x{to}"), |c| c.label.clone());
+                let function = contract.and_then(|c| c.abi.by_selector(*param1));
+                let mut payloads = vec![];
+                let (name, arguments) = match function {
+                    Some(f) if f.inputs.len() == 1 && f.inputs[0].1.is_single_word() => {
+                        (f.name.clone(), format!("({}: {param2})", f.inputs[0].0))
+                    }
+                    Some(f) if f.inputs.is_empty() => (f.name.clone(), "()".to_string()),
+                    Some(f) => {
+                        let caller_payloads = &stack.last().unwrap().payloads;
+                        let arguments = memory
+                            .and_then(|m| decode_at(f, m.from(*param2)?))
+                            .or_else(|| caller_payloads.iter().find_map(|p| encoding::decode_arguments(Encoding::Legacy, f, p).ok()));
+                        match arguments {
+                            Some(values) => (f.name.clone(), show_arguments(f, &values, memory, &mut payloads)),
+                            None => (f.name.clone(), format!("(ptr {param2:#x}) {}", f.signature())),
+                        }
+                    }
+                    None => (registry.describe(*param1), format!(" (param2 {param2:#x})")),
+                };
+                let mut node = CallNode::new(*to, label, name, arguments, *gas);
+                node.payloads = payloads;
+                stack.push(node);
+            }
+            Receipt::Log { ra, rb, .. } => {
+                top(&mut stack).logs.push(format!("log {ra:#x} (log id {rb})"));
+            }
+            Receipt::LogData { rb, data, .. } => {
+                top(&mut stack).logs.push(format!("log 0x{} (log id {rb})", hex::encode(data)));
+            }
+            Receipt::Return { val, .. } => close(&mut stack, Outcome::Returned(*val)),
+            Receipt::ReturnData { data, .. } => close(&mut stack, Outcome::ReturnedData(data.clone())),
+            Receipt::Revert { ra, .. } => close(&mut stack, Outcome::Reverted(*ra)),
+            Receipt::Panic { reason, .. } => {
+                if *reason.reason() == PanicReason::OutOfGas {
+                    let node = top(&mut stack);
+                    node.gas_used = Some(node.gas_forwarded);
+                }
+                close(&mut stack, Outcome::Panicked(format!("{:?}", reason.reason())));
+            }
+            Receipt::ScriptResult { gas_used, .. } => {
+                stack[0].gas_used = Some(*gas_used);
+            }
+            _ => {}
+        }
+    }
+
+    // frames still open were cut short by a revert or panic further in.
+    while stack.len() > 1 {
+        let node = stack.pop().unwrap();
+        top(&mut stack).children.push(node);
+    }
+    stack.pop().unwrap()
+}
+
+/// `function`'s arguments from the start of `bytes`, which may go on past them, in the
+/// legacy layout every call of this toolchain uses.
+fn decode_at(function: &AbiFunction, bytes: &[u8]) -> Option<Vec<AbiValue>> {
+    let mut offset = 0;
+    let mut values = vec![];
+    for (_, ty) in function.inputs.iter() {
+        let (value, used) = encoding::decode(Encoding::Legacy, ty, bytes.get(offset..)?).ok()?;
+        values.push(value);
+        offset += used;
+    }
+    Some(values)
+}
+
+fn show_arguments(function: &AbiFunction, values: &[AbiValue], memory: Option<&ScriptMemory>, payloads: &mut Vec<Vec<u8>>) -> String {
+    let arguments: Vec<String> = function
+        .inputs
+        .iter()
+        .zip(values)
+        .map(|((name, ty), value)| format!("{name}: {}", show(ty, value, memory, payloads)))
+        .collect();
+    format!("({})", arguments.join(", "))
+}
+
+///
+/// `value` as written in Sway. `Bytes` and `Vec` are read from `memory` when their buffer
+/// is there, and every `Bytes` read is added to `payloads`.
+///
+fn show(ty: &AbiType, value: &AbiValue, memory: Option<&ScriptMemory>, payloads: &mut Vec<Vec<u8>>) -> String {
+    match (ty, value) {
+        (AbiType::Struct { name, .. }, AbiValue::Struct(fields)) if name == "Bytes" => {
+            match memory.and_then(|m| m.read(pointer(fields), length(fields))) {
+                Some(bytes) => {
+                    payloads.push(bytes.to_vec());
+                    format!("0x{}", hex::encode(bytes))
+                }
+                None => format!("Bytes {{ {} at {:#x} }}", length(fields), pointer(fields)),
+            }
+        }
+        (AbiType::Struct { name, generics, .. }, AbiValue::Struct(fields)) if name == "Vec" && generics.len() == 1 => {
+            // the length counts elements, so the decoder decides how many bytes they take.
+            let elements = memory
+                .and_then(|m| m.from(pointer(fields)))
+                .and_then(|bytes| decode_elements(&generics[0], bytes, length(fields)));
+            match elements {
+                Some(elements) => format!("[{}]", show_list(&mut std::iter::repeat(&generics[0]), &elements, memory, payloads)),
+                None => format!("Vec {{ {} at {:#x} }}", length(fields), pointer(fields)),
+            }
+        }
+        (AbiType::Struct { name, fields: types, .. }, AbiValue::Struct(values)) => {
+            let fields: Vec<String> = types
+                .iter()
+                .zip(values)
+                .map(|((field, t), v)| format!("{field}: {}", show(t, v, memory, payloads)))
+                .collect();
+            format!("{name} {{ {} }}", fields.join(", "))
+        }
+        (AbiType::Enum { name, variants, .. }, AbiValue::Enum(index, inner)) => match variants.get(*index as usize) {
+            Some((variant, AbiType::Unit)) => format!("{name}::{variant}"),
+            Some((variant, t)) => format!("{name}::{variant}({})", show(t, inner, memory, payloads)),
+            None => format!("{name}::#{index}"),
+        },
+        (AbiType::Array(element, _), AbiValue::Array(values)) => {
+            format!("[{}]", show_list(&mut std::iter::repeat(element.as_ref()), values, memory, payloads))
+        }
+        (AbiType::Tuple(types), AbiValue::Tuple(values)) => format!("({})", show_list(&mut types.iter(), values, memory, payloads)),
+        (_, AbiValue::Unit) => "()".to_string(),
+        (_, AbiValue::Bool(b)) => b.to_string(),
+        (_, AbiValue::U8(n)) => n.to_string(),
+        (_, AbiValue::U16(n)) => n.to_string(),
+        (_, AbiValue::U32(n)) => n.to_string(),
+        (_, AbiValue::U64(n)) => n.to_string(),
+        (_, AbiValue::B256(b)) => format!("0x{}", hex::encode(b)),
+        (_, AbiValue::RawPtr(ptr)) => format!("ptr {ptr:#x}"),
+        (_, AbiValue::Str(text)) => format!("{text:?}"),
+        (_, other) => format!("{other:?}"),
+    }
+}
+
+fn show_list<'a>(
+    types: &mut dyn Iterator<Item = &'a AbiType>,
+    values: &[AbiValue],
+    memory: Option<&ScriptMemory>,
+    payloads: &mut Vec<Vec<u8>>,
+) -> String {
+    types.zip(values).map(|(t, v)| show(t, v, memory, payloads)).collect::<Vec<_>>().join(", ")
+}
+
+/// `Bytes` and `Vec` are `{ buf: { ptr, cap }, len }`.
+fn pointer(fields: &[AbiValue]) -> u64 {
+    match fields.first() {
+        Some(AbiValue::Struct(buf)) => match buf.first() {
+            Some(AbiValue::RawPtr(ptr)) => *ptr,
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+fn length(fields: &[AbiValue]) -> u64 {
+    match fields.get(1) {
+        Some(AbiValue::U64(len)) => *len,
+        _ => 0,
+    }
+}
+
+fn decode_elements(element: &AbiType, bytes: &[u8], len: u64) -> Option<Vec<AbiValue>> {
+    let mut offset = 0;
+    let mut values = vec![];
+    for _ in 0..len {
+        let (value, used) = encoding::decode(Encoding::Legacy, element, bytes.get(offset..)?).ok()?;
+        values.push(value);
+        offset += used;
+    }
+    Some(values)
+}
+
+fn top(stack: &mut [CallNode]) -> &mut CallNode {
+    stack.last_mut().unwrap()
+}
+
+/// Sets the outcome of the innermost frame and hangs it under its caller.
+/// The script's own Return / Revert / Panic only sets the root's outcome.
+fn close(stack: &mut Vec<CallNode>, outcome: Outcome) {
+    top(stack).outcome = outcome;
+    if stack.len() > 1 {
+        let node = stack.pop().unwrap();
+        top(stack).children.push(node);
+    }
+}
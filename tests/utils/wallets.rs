@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use fuels::{
+    accounts::fuel_crypto::SecretKey,
+    prelude::*,
+};
+
+use crate::utils::config::{HarnessConfig, Node};
+
+/// Base asset each generated signer is funded with.
+pub const DEFAULT_SIGNER_FUNDING: u64 = 10_000_000;
+
+/// Fuel's BIP-44 coin type, as used by the SDK and the forc wallet.
+const FUEL_DERIVATION_PATH_PREFIX: &str = "m/44'/1179993420'";
+
+///
+/// Decrypts a password-protected keystore file (the format written by
+/// `WalletUnlocked::encrypt` / forc-wallet) into its secret key.
+///
+pub fn secret_key_from_keystore(path: &Path, password: &str) -> Result<SecretKey> {
+    let secret = eth_keystore::decrypt_key(path, password)
+        .map_err(|e| Error::WalletError(format!("cannot decrypt keystore {}: {e}", path.display())))?;
+    SecretKey::try_from(secret.as_slice())
+        .map_err(|e| Error::WalletError(format!("keystore {} holds an invalid key: {e}", path.display())))
+}
+
+///
+/// Derives `count` wallets from `phrase`, one per account index (m/44'/1179993420'/i'/0/0).
+///
+pub fn wallets_from_mnemonic(phrase: &str, count: usize, provider: &Provider) -> Result<Vec<WalletUnlocked>> {
+    (0..count)
+        .map(|i| {
+            let path = format!("{FUEL_DERIVATION_PATH_PREFIX}/{i}'/0/0");
+            WalletUnlocked::new_from_mnemonic_phrase_with_path(phrase, Some(provider.clone()), &path)
+        })
+        .collect()
+}
+
+///
+/// True for nodes we may move dev funds on: embedded ones and ones on this machine.
+/// Only the exact host counts, so `localhost.example.com` is not local.
+///
+pub fn is_local_node(node: &Node) -> bool {
+    match node {
+        Node::Embedded => true,
+        Node::Remote(url) => {
+            // LLCALL_NODE_URL may leave out the scheme, e.g. 127.0.0.1:4000.
+            let url = if url.contains("://") { url.to_string() } else { format!("http://{url}") };
+            match reqwest::Url::parse(&url) {
+                Ok(url) => matches!(url.host_str(), Some("127.0.0.1" | "localhost" | "0.0.0.0" | "[::1]")),
+                Err(_) => false,
+            }
+        }
+    }
+}
+
+///
+/// Sends `amount` of the base asset from `funder` to each of `wallets`.
+/// Refuses to run against anything but a local node.
+///
+pub async fn fund(config: &HarnessConfig, funder: &WalletUnlocked, wallets: &[WalletUnlocked], amount: u64) -> Result<()> {
+    if !is_local_node(&config.node) {
+        return Err(Error::WalletError(format!(
+            "refusing to fund signers on {:?}, only local nodes are funded from the dev wallet",
+            config.node
+        )));
+    }
+    for wallet in wallets {
+        funder
+            .transfer(wallet.address(), amount, BASE_ASSET_ID, TxParameters::default())
+            .await?;
+    }
+    Ok(())
+}
+
+///
+/// `n` independent signers for a test.
+///
+/// With a mnemonic configured they are derived from it, and only funded from `funder` on a
+/// local node: elsewhere they are expected to hold funds already. Otherwise `n` fresh
+/// random wallets are generated and funded from `funder`.
+///
+pub async fn signers(config: &HarnessConfig, funder: &WalletUnlocked, n: usize) -> Result<Vec<WalletUnlocked>> {
+    let provider = funder.try_provider()?.clone();
+
+    if let Some(phrase) = config.mnemonic.as_deref() {
+        let wallets = wallets_from_mnemonic(phrase, n, &provider)?;
+        if is_local_node(&config.node) {
+            fund(config, funder, &wallets, DEFAULT_SIGNER_FUNDING).await?;
+        }
+        return Ok(wallets);
+    }
+
+    let wallets: Vec<WalletUnlocked> = (0..n)
+        .map(|_| WalletUnlocked::new_random(Some(provider.clone())))
+        .collect();
+    fund(config, funder, &wallets, DEFAULT_SIGNER_FUNDING).await?;
+    Ok(wallets)
+}